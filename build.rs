@@ -25,7 +25,7 @@ fn main() {
         ("WGPUCommandBuffer", "CommandBufferId"),
         ("WGPURenderPassEncoder", "RenderPassEncoderId"),
         ("WGPUComputePassEncoder", "ComputePassEncoderId"),
-        ("WGPURenderBundleEncoder", "ComputePipelineId"),
+        ("WGPURenderBundleEncoder", "RenderBundleEncoderId"),
         ("WGPURenderBundle", "RenderBundleId"),
         ("WGPUQuerySet", "QuerySetId"),
     ];
@@ -61,6 +61,20 @@ fn main() {
             }",
     );
 
+    // WGPURenderPassColorAttachment.resolveTarget can be Null
+    builder = builder
+        .blocklist_item("WGPURenderPassColorAttachment")
+        .raw_line(
+            "#[repr(C)]
+            pub struct WGPURenderPassColorAttachment {
+                pub view: wgc::id::TextureViewId,
+                pub resolveTarget: Option<wgc::id::TextureViewId>,
+                pub loadOp: crate::native::WGPULoadOp,
+                pub storeOp: crate::native::WGPUStoreOp,
+                pub clearColor: crate::native::WGPUColor,
+            }",
+        );
+
     // WGPURequestAdapterOptions.compatibleSurface can be Null
     builder = builder
         .blocklist_item("WGPURequestAdapterOptions")
@@ -74,6 +88,20 @@ fn main() {
             }",
         );
 
+    // WGPURequestDeviceCallback is called with a Null device when the descriptor is rejected
+    builder = builder
+        .blocklist_item("WGPURequestDeviceCallback")
+        .raw_line(
+            "pub type WGPURequestDeviceCallback = Option<
+                unsafe extern \"C\" fn(
+                    status: crate::native::WGPURequestDeviceStatus,
+                    device: Option<wgc::id::DeviceId>,
+                    message: *const std::os::raw::c_char,
+                    userdata: *mut std::os::raw::c_void,
+                ),
+            >;",
+        );
+
     // See https://github.com/rust-lang/rust-bindgen/issues/1780
     if let Ok("ios") = env::var("CARGO_CFG_TARGET_OS").as_ref().map(|x| &**x) {
         let output = Command::new("xcrun")
@@ -7,13 +7,61 @@ typedef enum WGPUNativeSType {
     // Start at 6 to prevent collisions with webgpu STypes
     WGPUSType_DeviceExtras = 0x60000001,
     WGPUSType_AdapterExtras = 0x60000002,
+    WGPUSType_RequiredLimitsExtras = 0x60000003,
+    WGPUSType_PipelineLayoutExtras = 0x60000004,
+    WGPUSType_SamplerDescriptorExtras = 0x60000005,
+    WGPUSType_InstanceExtras = 0x60000006,
+    WGPUSType_SurfaceDescriptorFromXcbWindow = 0x60000007,
+    WGPUSType_BindGroupLayoutEntryExtras = 0x60000008,
+    WGPUSType_BindGroupEntryExtras = 0x60000009,
     WGPUNativeSType_Force32 = 0x7FFFFFFF
 } WGPUNativeSType;
 
 typedef enum WGPUNativeFeature {
-    WGPUNativeFeature_TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES = 0x10000000
+    WGPUNativeFeature_TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES = 0x10000000,
+    WGPUNativeFeature_PUSH_CONSTANTS = 0x20000000,
+    WGPUNativeFeature_MULTI_DRAW_INDIRECT_COUNT = 0x40000000,
+    WGPUNativeFeature_CLEAR_COMMANDS = 0x08000000,
+    WGPUNativeFeature_ADDRESS_MODE_CLAMP_TO_BORDER = 0x04000000,
+    WGPUNativeFeature_TEXTURE_BINDING_ARRAY = 0x02000000
 } WGPUNativeFeature;
 
+typedef enum WGPUNativeAddressMode {
+    // Start at 0x60000000 to prevent collisions with webgpu address modes
+    WGPUAddressMode_ClampToBorder = 0x60000000,
+    WGPUNativeAddressMode_Force32 = 0x7FFFFFFF
+} WGPUNativeAddressMode;
+
+typedef enum WGPUSamplerBorderColor {
+    WGPUSamplerBorderColor_TransparentBlack = 0x00000000,
+    WGPUSamplerBorderColor_OpaqueBlack = 0x00000001,
+    WGPUSamplerBorderColor_OpaqueWhite = 0x00000002,
+    WGPUSamplerBorderColor_Force32 = 0x7FFFFFFF
+} WGPUSamplerBorderColor;
+
+typedef enum WGPUSwapChainStatus {
+    WGPUSwapChainStatus_Good = 0x00000000,
+    WGPUSwapChainStatus_Suboptimal = 0x00000001,
+    WGPUSwapChainStatus_Timeout = 0x00000002,
+    WGPUSwapChainStatus_Outdated = 0x00000003,
+    WGPUSwapChainStatus_Lost = 0x00000004,
+    WGPUSwapChainStatus_Force32 = 0x7FFFFFFF
+} WGPUSwapChainStatus;
+
+typedef enum WGPUInstanceBackend {
+    WGPUInstanceBackend_Vulkan = 1 << 1,
+    WGPUInstanceBackend_Metal = 1 << 2,
+    WGPUInstanceBackend_DX12 = 1 << 3,
+    WGPUInstanceBackend_DX11 = 1 << 4,
+    WGPUInstanceBackend_GL = 1 << 5,
+    WGPUInstanceBackend_BrowserWebGPU = 1 << 6,
+    WGPUInstanceBackend_Primary = WGPUInstanceBackend_Vulkan | WGPUInstanceBackend_Metal |
+        WGPUInstanceBackend_DX12 | WGPUInstanceBackend_BrowserWebGPU,
+    WGPUInstanceBackend_Secondary = WGPUInstanceBackend_GL | WGPUInstanceBackend_DX11,
+    WGPUInstanceBackend_Force32 = 0x7FFFFFFF
+} WGPUInstanceBackend;
+typedef WGPUFlags WGPUInstanceBackendFlags;
+
 typedef enum WGPULogLevel {
     WGPULogLevel_Off = 0x00000000,
     WGPULogLevel_Error = 0x00000001,
@@ -24,6 +72,19 @@ typedef enum WGPULogLevel {
     WGPULogLevel_Force32 = 0x7FFFFFFF
 } WGPULogLevel;
 
+typedef struct WGPUInstanceExtras {
+    WGPUChainedStruct chain;
+    // Backends the instance is created with, PRIMARY if 0. Only honored when
+    // wgpuCreateInstance is called before any other function.
+    WGPUInstanceBackendFlags backends;
+} WGPUInstanceExtras;
+
+typedef struct WGPUSurfaceDescriptorFromXcbWindow {
+    WGPUChainedStruct chain;
+    void * connection;
+    uint32_t window;
+} WGPUSurfaceDescriptorFromXcbWindow;
+
 typedef struct WGPUAdapterExtras {
     WGPUChainedStruct chain;
     WGPUBackendType backend;
@@ -36,21 +97,129 @@ typedef struct WGPUDeviceExtras {
     const char* tracePath;
 } WGPUDeviceExtras;
 
+typedef struct WGPURequiredLimitsExtras {
+    WGPUChainedStruct chain;
+    uint32_t maxPushConstantSize;
+} WGPURequiredLimitsExtras;
+
+typedef struct WGPUPushConstantRange {
+    WGPUShaderStageFlags stages;
+    uint32_t start;
+    uint32_t end;
+} WGPUPushConstantRange;
+
+typedef struct WGPUPipelineLayoutExtras {
+    WGPUChainedStruct chain;
+    uint32_t pushConstantRangeCount;
+    WGPUPushConstantRange* pushConstantRanges;
+} WGPUPipelineLayoutExtras;
+
+typedef struct WGPUBindGroupLayoutEntryExtras {
+    WGPUChainedStruct chain;
+    // Number of elements in a binding array, 0 for a single binding.
+    uint32_t count;
+} WGPUBindGroupLayoutEntryExtras;
+
+typedef struct WGPUBindGroupEntryExtras {
+    WGPUChainedStruct chain;
+    WGPUTextureView const * textureViews;
+    uint32_t textureViewCount;
+} WGPUBindGroupEntryExtras;
+
+typedef struct WGPUSamplerDescriptorExtras {
+    WGPUChainedStruct chain;
+    WGPUSamplerBorderColor borderColor;
+} WGPUSamplerDescriptorExtras;
+
+typedef struct WGPUImageSubresourceRange {
+    WGPUTextureAspect aspect;
+    uint32_t baseMipLevel;
+    uint32_t mipLevelCount;
+    uint32_t baseArrayLayer;
+    uint32_t arrayLayerCount;
+} WGPUImageSubresourceRange;
+
+typedef struct WGPUStorageReport {
+    size_t numOccupied;
+    size_t numVacant;
+    size_t numError;
+    size_t elementSize;
+} WGPUStorageReport;
+
+typedef struct WGPUHubReport {
+    WGPUStorageReport adapters;
+    WGPUStorageReport devices;
+    WGPUStorageReport pipelineLayouts;
+    WGPUStorageReport shaderModules;
+    WGPUStorageReport bindGroupLayouts;
+    WGPUStorageReport bindGroups;
+    WGPUStorageReport commandBuffers;
+    WGPUStorageReport renderBundles;
+    WGPUStorageReport renderPipelines;
+    WGPUStorageReport computePipelines;
+    WGPUStorageReport querySets;
+    WGPUStorageReport buffers;
+    WGPUStorageReport textures;
+    WGPUStorageReport textureViews;
+    WGPUStorageReport samplers;
+} WGPUHubReport;
+
+typedef struct WGPUGlobalReport {
+    WGPUStorageReport surfaces;
+    // First of Vulkan, Metal, DX12, GL with a hub in use, Null if none.
+    WGPUBackendType backendType;
+    WGPUHubReport vulkan;
+    WGPUHubReport metal;
+    WGPUHubReport dx12;
+    // Always zero, DX11 isn't supported yet.
+    WGPUHubReport dx11;
+    WGPUHubReport gl;
+} WGPUGlobalReport;
+
 typedef void (*WGPULogCallback)(WGPULogLevel level, const char *msg);
 
+typedef void (*WGPUEnumerateAdaptersCallback)(WGPUAdapter adapter, void * userdata);
+
 #ifdef __cplusplus
 extern "C" {
 #endif
 
+void wgpuInstanceEnumerateAdapters(WGPUInstance instance, WGPUInstanceBackendFlags backends, WGPUEnumerateAdaptersCallback callback, void * userdata);
+
+void wgpuAdapterPropertiesFreeMembers(WGPUAdapterProperties properties);
+
+void wgpuGenerateReport(WGPUGlobalReport * report);
+
 void wgpuDevicePoll(WGPUDevice device, bool force_wait);
 
+void * wgpuBufferMapSync(WGPUBuffer buffer, WGPUMapModeFlags mode, size_t offset, size_t size);
+
+void wgpuSwapChainResize(WGPUSwapChain swapChain, uint32_t width, uint32_t height);
+
+// Returns NULL unless the status is Good or Suboptimal. Outdated means the swap chain
+// has to be resized before the next frame. Unlike wgpuSwapChainGetCurrentTextureView,
+// the status is not reported as a device error.
+WGPUTextureView wgpuSwapChainGetCurrentTextureViewWithStatus(WGPUSwapChain swapChain, WGPUSwapChainStatus * status);
+
 void wgpuSetLogCallback(WGPULogCallback callback);
 
 void wgpuSetLogLevel(WGPULogLevel level);
 
 uint32_t wgpuGetVersion(void);
 
+float wgpuQueueGetTimestampPeriod(WGPUQueue queue);
+
+void wgpuCommandEncoderClearBuffer(WGPUCommandEncoder commandEncoder, WGPUBuffer buffer, uint64_t offset, uint64_t size);
+void wgpuCommandEncoderClearTexture(WGPUCommandEncoder commandEncoder, WGPUTexture texture, WGPUImageSubresourceRange const * range);
+
+// Returns 0 for an undefined format or when the padded row doesn't fit in 32 bits.
+uint32_t wgpuGetAlignedBytesPerRow(WGPUTextureFormat format, uint32_t width);
+
 void wgpuRenderPassEncoderSetPushConstants(WGPURenderPassEncoder encoder, WGPUShaderStageFlags stages, uint32_t offset, uint32_t sizeBytes, void* const data);
+void wgpuComputePassEncoderSetPushConstants(WGPUComputePassEncoder encoder, uint32_t offset, uint32_t sizeBytes, void* const data);
+
+void wgpuRenderPassEncoderMultiDrawIndirectCount(WGPURenderPassEncoder encoder, WGPUBuffer buffer, uint64_t offset, WGPUBuffer count_buffer, uint64_t count_buffer_offset, uint32_t max_count);
+void wgpuRenderPassEncoderMultiDrawIndexedIndirectCount(WGPURenderPassEncoder encoder, WGPUBuffer buffer, uint64_t offset, WGPUBuffer count_buffer, uint64_t count_buffer_offset, uint32_t max_count);
 
 void wgpuBufferDrop(WGPUBuffer buffer);
 void wgpuCommandEncoderDrop(WGPUCommandEncoder commandEncoder);
@@ -1,7 +1,11 @@
-use crate::{conv, make_slice, native, OwnedLabel, GLOBAL};
-use std::{borrow::Cow, num::NonZeroU64};
+use crate::device::{get_device_from_command_encoder, handle_command_encoder_error};
+use crate::{
+    conv, handle_device_error, handle_device_error_raw, make_slice, native, OwnedLabel, GLOBAL,
+};
+use lazy_static::lazy_static;
+use std::{borrow::Cow, collections::HashMap, marker::PhantomData, num::NonZeroU64, sync::Mutex};
 use wgc::{
-    command::{compute_ffi, render_ffi},
+    command::{bundle_ffi, compute_ffi, render_ffi},
     gfx_select, id,
 };
 
@@ -14,11 +18,24 @@ pub unsafe extern "C" fn wgpuCommandEncoderFinish(
         label: OwnedLabel::new(descriptor.label).into_cow(),
     };
 
+    let pass_open = OPEN_PASSES.lock().unwrap().remove(&encoder).is_some();
+
     let (id, error) = gfx_select!(encoder => GLOBAL.command_encoder_finish(encoder, &desc));
     if let Some(error) = error {
-        // TODO figure out what device the encoder belongs to and call
-        // handle_device_error()
-        log::error!("command_encoder_finish() failed: {:?}", error);
+        handle_command_encoder_error(encoder, &error);
+        None
+    } else if pass_open {
+        // Passes are only recorded into the encoder when they end, so a pass that is
+        // still open would silently be missing from the command buffer. The command
+        // buffer is withheld, like on the error path above the caller still owns the
+        // encoder and releases it with wgpuCommandEncoderDrop.
+        let message = "Command encoder finished while a pass is still open";
+        match get_device_from_command_encoder(encoder) {
+            Some(device) => {
+                handle_device_error_raw(device, native::WGPUErrorType_Validation, message)
+            }
+            None => log::error!("{}", message),
+        }
         None
     } else {
         Some(id)
@@ -34,14 +51,16 @@ pub unsafe extern "C" fn wgpuCommandEncoderCopyBufferToBuffer(
     destination_offset: u64,
     size: u64,
 ) {
-    gfx_select!(command_encoder => GLOBAL.command_encoder_copy_buffer_to_buffer(
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_copy_buffer_to_buffer(
         command_encoder,
         source,
         source_offset,
         destination,
         destination_offset,
         size))
-    .expect("Unable to copy buffer to buffer")
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
 }
 
 #[no_mangle]
@@ -51,12 +70,14 @@ pub extern "C" fn wgpuCommandEncoderCopyTextureToTexture(
     destination: &native::WGPUImageCopyTexture,
     copy_size: &native::WGPUExtent3D,
 ) {
-    gfx_select!(command_encoder => GLOBAL.command_encoder_copy_texture_to_texture(
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_copy_texture_to_texture(
         command_encoder,
         &conv::map_image_copy_texture(source),
         &conv::map_image_copy_texture(destination),
         &conv::map_extent3d(copy_size)))
-    .expect("Unable to copy texture to texture")
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
 }
 
 #[no_mangle]
@@ -66,12 +87,14 @@ pub extern "C" fn wgpuCommandEncoderCopyTextureToBuffer(
     destination: &native::WGPUImageCopyBuffer,
     copy_size: &native::WGPUExtent3D,
 ) {
-    gfx_select!(command_encoder => GLOBAL.command_encoder_copy_texture_to_buffer(
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_copy_texture_to_buffer(
         command_encoder,
         &conv::map_image_copy_texture(source),
         &conv::map_image_copy_buffer(destination),
         &conv::map_extent3d(copy_size)))
-    .expect("Unable to copy texture to buffer")
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
 }
 
 #[no_mangle]
@@ -81,12 +104,153 @@ pub extern "C" fn wgpuCommandEncoderCopyBufferToTexture(
     destination: &native::WGPUImageCopyTexture,
     copy_size: &native::WGPUExtent3D,
 ) {
-    gfx_select!(command_encoder => GLOBAL.command_encoder_copy_buffer_to_texture(
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_copy_buffer_to_texture(
         command_encoder,
         &conv::map_image_copy_buffer(source),
         &conv::map_image_copy_texture(destination),
         &conv::map_extent3d(copy_size)))
-    .expect("Unable to copy buffer to texture")
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn wgpuCommandEncoderClearBuffer(
+    command_encoder: id::CommandEncoderId,
+    buffer: id::BufferId,
+    offset: u64,
+    size: u64,
+) {
+    // A size of 0 clears the rest of the buffer starting at offset.
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_clear_buffer(
+        command_encoder,
+        buffer,
+        offset,
+        NonZeroU64::new(size)))
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn wgpuCommandEncoderClearTexture(
+    command_encoder: id::CommandEncoderId,
+    texture: id::TextureId,
+    range: &native::WGPUImageSubresourceRange,
+) {
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_clear_texture(
+        command_encoder,
+        texture,
+        &conv::map_image_subresource_range(range)))
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn wgpuGetAlignedBytesPerRow(format: native::WGPUTextureFormat, width: u32) -> u32 {
+    // 0 is never a valid padded row size, it signals an unknown format or an overflow.
+    conv::map_texture_format(format)
+        .and_then(|format| conv::aligned_bytes_per_row(format, width))
+        .unwrap_or(0)
+}
+
+#[no_mangle]
+pub extern "C" fn wgpuCommandEncoderWriteTimestamp(
+    command_encoder: id::CommandEncoderId,
+    query_set: id::QuerySetId,
+    query_index: u32,
+) {
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_write_timestamp(
+        command_encoder,
+        query_set,
+        query_index))
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn wgpuCommandEncoderResolveQuerySet(
+    command_encoder: id::CommandEncoderId,
+    query_set: id::QuerySetId,
+    first_query: u32,
+    query_count: u32,
+    destination: id::BufferId,
+    destination_offset: u64,
+) {
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_resolve_query_set(
+        command_encoder,
+        query_set,
+        first_query,
+        query_count,
+        destination,
+        destination_offset))
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuCommandEncoderInsertDebugMarker(
+    command_encoder: id::CommandEncoderId,
+    marker_label: *const std::os::raw::c_char,
+) {
+    let label = OwnedLabel::new(marker_label);
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_insert_debug_marker(
+        command_encoder,
+        label.as_cow().as_deref().unwrap_or_default()))
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuCommandEncoderPushDebugGroup(
+    command_encoder: id::CommandEncoderId,
+    group_label: *const std::os::raw::c_char,
+) {
+    let label = OwnedLabel::new(group_label);
+    if let Err(error) = gfx_select!(command_encoder => GLOBAL.command_encoder_push_debug_group(
+        command_encoder,
+        label.as_cow().as_deref().unwrap_or_default()))
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuCommandEncoderPopDebugGroup(command_encoder: id::CommandEncoderId) {
+    if let Err(error) =
+        gfx_select!(command_encoder => GLOBAL.command_encoder_pop_debug_group(command_encoder))
+    {
+        handle_command_encoder_error(command_encoder, &error);
+    }
+}
+
+lazy_static! {
+    // Number of passes begun but not yet ended, per encoder.
+    static ref OPEN_PASSES: Mutex<HashMap<id::CommandEncoderId, u32>> =
+        Mutex::new(HashMap::new());
+}
+
+fn begin_pass(encoder: id::CommandEncoderId) {
+    *OPEN_PASSES.lock().unwrap().entry(encoder).or_insert(0) += 1;
+}
+
+// Called when the encoder is dropped, passes left open on it can never end.
+pub(crate) fn forget_open_passes(encoder: id::CommandEncoderId) {
+    OPEN_PASSES.lock().unwrap().remove(&encoder);
+}
+
+fn end_pass(encoder: id::CommandEncoderId) {
+    let mut open_passes = OPEN_PASSES.lock().unwrap();
+    if let Some(count) = open_passes.get_mut(&encoder) {
+        *count -= 1;
+        if *count == 0 {
+            open_passes.remove(&encoder);
+        }
+    }
 }
 
 #[no_mangle]
@@ -98,6 +262,7 @@ pub unsafe extern "C" fn wgpuCommandEncoderBeginComputePass(
         label: OwnedLabel::new(descriptor.label).into_cow(),
     };
     let pass = wgc::command::ComputePass::new(encoder, &desc);
+    begin_pass(encoder);
     Box::into_raw(Box::new(pass))
 }
 
@@ -133,7 +298,7 @@ pub unsafe extern "C" fn wgpuCommandEncoderBeginRenderPass(
             .iter()
             .map(|color_attachment| wgc::command::RenderPassColorAttachment {
                 view: color_attachment.view,
-                resolve_target: Some(color_attachment.resolveTarget),
+                resolve_target: color_attachment.resolveTarget,
                 channel: wgc::command::PassChannel {
                     load_op: conv::map_load_op(color_attachment.loadOp),
                     store_op: conv::map_store_op(color_attachment.storeOp),
@@ -146,6 +311,7 @@ pub unsafe extern "C" fn wgpuCommandEncoderBeginRenderPass(
         depth_stencil_attachment: depth_stencil_attachment.as_ref(),
     };
     let pass = wgc::command::RenderPass::new(encoder, &desc);
+    begin_pass(encoder);
     Box::into_raw(Box::new(pass))
 }
 
@@ -153,16 +319,24 @@ pub unsafe extern "C" fn wgpuCommandEncoderBeginRenderPass(
 pub unsafe extern "C" fn wgpuComputePassEncoderEndPass(pass: id::ComputePassEncoderId) {
     let pass = Box::from_raw(pass);
     let encoder_id = pass.parent_id();
-    gfx_select!(encoder_id => GLOBAL.command_encoder_run_compute_pass(encoder_id, &pass))
-        .expect("Unable to end compute pass");
+    end_pass(encoder_id);
+    if let Err(error) =
+        gfx_select!(encoder_id => GLOBAL.command_encoder_run_compute_pass(encoder_id, &pass))
+    {
+        handle_command_encoder_error(encoder_id, &error);
+    }
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn wgpuRenderPassEncoderEndPass(pass: id::RenderPassEncoderId) {
     let pass = Box::from_raw(pass);
     let encoder_id = pass.parent_id();
-    gfx_select!(encoder_id => GLOBAL.command_encoder_run_render_pass(encoder_id, &pass))
-        .expect("Unable to end render pass");
+    end_pass(encoder_id);
+    if let Err(error) =
+        gfx_select!(encoder_id => GLOBAL.command_encoder_run_render_pass(encoder_id, &pass))
+    {
+        handle_command_encoder_error(encoder_id, &error);
+    }
 }
 
 // TODO: Move these out of wgc
@@ -220,6 +394,74 @@ pub unsafe extern "C" fn wgpuRenderPassEncoderSetBindGroup(
     );
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wgpuComputePassEncoderWriteTimestamp(
+    pass: id::ComputePassEncoderId,
+    query_set: id::QuerySetId,
+    query_index: u32,
+) {
+    let pass = pass.as_mut().expect("Compute pass invalid");
+    compute_ffi::wgpu_compute_pass_write_timestamp(pass, query_set, query_index);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderPassEncoderWriteTimestamp(
+    pass: id::RenderPassEncoderId,
+    query_set: id::QuerySetId,
+    query_index: u32,
+) {
+    let pass = pass.as_mut().expect("Render pass invalid");
+    render_ffi::wgpu_render_pass_write_timestamp(pass, query_set, query_index);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuComputePassEncoderInsertDebugMarker(
+    pass: id::ComputePassEncoderId,
+    marker_label: *const std::os::raw::c_char,
+) {
+    let pass = pass.as_mut().expect("Compute pass invalid");
+    compute_ffi::wgpu_compute_pass_insert_debug_marker(pass, marker_label, 0);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuComputePassEncoderPushDebugGroup(
+    pass: id::ComputePassEncoderId,
+    group_label: *const std::os::raw::c_char,
+) {
+    let pass = pass.as_mut().expect("Compute pass invalid");
+    compute_ffi::wgpu_compute_pass_push_debug_group(pass, group_label, 0);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuComputePassEncoderPopDebugGroup(pass: id::ComputePassEncoderId) {
+    let pass = pass.as_mut().expect("Compute pass invalid");
+    compute_ffi::wgpu_compute_pass_pop_debug_group(pass);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderPassEncoderInsertDebugMarker(
+    pass: id::RenderPassEncoderId,
+    marker_label: *const std::os::raw::c_char,
+) {
+    let pass = pass.as_mut().expect("Render pass invalid");
+    render_ffi::wgpu_render_pass_insert_debug_marker(pass, marker_label, 0);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderPassEncoderPushDebugGroup(
+    pass: id::RenderPassEncoderId,
+    group_label: *const std::os::raw::c_char,
+) {
+    let pass = pass.as_mut().expect("Render pass invalid");
+    render_ffi::wgpu_render_pass_push_debug_group(pass, group_label, 0);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderPassEncoderPopDebugGroup(pass: id::RenderPassEncoderId) {
+    let pass = pass.as_mut().expect("Render pass invalid");
+    render_ffi::wgpu_render_pass_pop_debug_group(pass);
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wgpuComputePassEncoderDispatch(
     pass: id::ComputePassEncoderId,
@@ -265,7 +507,7 @@ pub unsafe extern "C" fn wgpuRenderPassEncoderDrawIndexed(
     index_count: u32,
     instance_count: u32,
     first_index: u32,
-    base_vertex: u32,
+    base_vertex: i32,
     first_instance: u32,
 ) {
     let pass = pass.as_mut().expect("Render pass invalid");
@@ -274,7 +516,7 @@ pub unsafe extern "C" fn wgpuRenderPassEncoderDrawIndexed(
         index_count,
         instance_count,
         first_index,
-        base_vertex as i32,
+        base_vertex,
         first_instance,
     );
 }
@@ -299,6 +541,46 @@ pub unsafe extern "C" fn wgpuRenderPassEncoderDrawIndexedIndirect(
     render_ffi::wgpu_render_pass_draw_indexed_indirect(pass, buffer, indirect_offset);
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderPassEncoderMultiDrawIndirectCount(
+    pass: id::RenderPassEncoderId,
+    buffer: id::BufferId,
+    offset: u64,
+    count_buffer: id::BufferId,
+    count_buffer_offset: u64,
+    max_count: u32,
+) {
+    let pass = pass.as_mut().expect("Render pass invalid");
+    render_ffi::wgpu_render_pass_multi_draw_indirect_count(
+        pass,
+        buffer,
+        offset,
+        count_buffer,
+        count_buffer_offset,
+        max_count,
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderPassEncoderMultiDrawIndexedIndirectCount(
+    pass: id::RenderPassEncoderId,
+    buffer: id::BufferId,
+    offset: u64,
+    count_buffer: id::BufferId,
+    count_buffer_offset: u64,
+    max_count: u32,
+) {
+    let pass = pass.as_mut().expect("Render pass invalid");
+    render_ffi::wgpu_render_pass_multi_draw_indexed_indirect_count(
+        pass,
+        buffer,
+        offset,
+        count_buffer,
+        count_buffer_offset,
+        max_count,
+    );
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wgpuRenderPassEncoderSetIndexBuffer(
     pass: id::RenderPassEncoderId,
@@ -337,21 +619,32 @@ pub unsafe extern "C" fn wgpuRenderPassEncoderSetVertexBuffer(
 #[no_mangle]
 pub unsafe extern "C" fn wgpuRenderPassEncoderSetPushConstants(
     pass: id::RenderPassEncoderId,
-    stages: &native::WGPUShaderStage,
+    stages: native::WGPUShaderStageFlags,
     offset: u32,
     size_bytes: u32,
-    size: *const u8,
+    data: *const u8,
 ) {
     let pass = pass.as_mut().expect("Render pass invalid");
     render_ffi::wgpu_render_pass_set_push_constants(
         pass,
-        wgt::ShaderStages::from_bits(*stages as u32).expect("Invalid shader stage"),
+        wgt::ShaderStages::from_bits(stages).expect("Invalid shader stage"),
         offset,
         size_bytes,
-        size,
+        data,
     );
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wgpuComputePassEncoderSetPushConstants(
+    pass: id::ComputePassEncoderId,
+    offset: u32,
+    size_bytes: u32,
+    data: *const u8,
+) {
+    let pass = pass.as_mut().expect("Compute pass invalid");
+    compute_ffi::wgpu_compute_pass_set_push_constant(pass, offset, size_bytes, data);
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wgpuRenderPassEncoderSetBlendConstant(
     pass: id::RenderPassEncoderId,
@@ -395,3 +688,236 @@ pub unsafe extern "C" fn wgpuRenderPassEncoderSetScissorRect(
     let pass = pass.as_mut().expect("Render pass invalid");
     render_ffi::wgpu_render_pass_set_scissor_rect(pass, x, y, w, h);
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderPassEncoderExecuteBundles(
+    pass: id::RenderPassEncoderId,
+    bundles_count: u32,
+    bundles: *const id::RenderBundleId,
+) {
+    let pass = pass.as_mut().expect("Render pass invalid");
+    render_ffi::wgpu_render_pass_execute_bundles(pass, bundles, bundles_count as usize);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuDeviceCreateRenderBundleEncoder(
+    device: id::DeviceId,
+    descriptor: &native::WGPURenderBundleEncoderDescriptor,
+) -> id::RenderBundleEncoderId {
+    let desc = wgc::command::RenderBundleEncoderDescriptor {
+        label: OwnedLabel::new(descriptor.label).into_cow(),
+        color_formats: Cow::Owned(
+            make_slice(
+                descriptor.colorFormats,
+                descriptor.colorFormatsCount as usize,
+            )
+            .iter()
+            .map(|format| conv::map_texture_format(*format).expect("Color format must be defined"))
+            .collect(),
+        ),
+        depth_stencil: conv::map_texture_format(descriptor.depthStencilFormat).map(|format| {
+            wgt::RenderBundleDepthStencil {
+                format,
+                depth_read_only: false,
+                stencil_read_only: false,
+            }
+        }),
+        sample_count: descriptor.sampleCount,
+        multiview: None,
+    };
+    match wgc::command::RenderBundleEncoder::new(&desc, device, None) {
+        Ok(encoder) => Box::into_raw(Box::new(encoder)),
+        Err(error) => {
+            handle_device_error(device, &error);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderFinish(
+    bundle_encoder: id::RenderBundleEncoderId,
+    descriptor: &native::WGPURenderBundleDescriptor,
+) -> Option<id::RenderBundleId> {
+    let bundle_encoder = Box::from_raw(bundle_encoder);
+    let device = bundle_encoder.parent();
+    let desc = wgt::RenderBundleDescriptor {
+        label: OwnedLabel::new(descriptor.label).into_cow(),
+    };
+
+    let (id, error) = gfx_select!(device => GLOBAL.render_bundle_encoder_finish(*bundle_encoder, &desc, PhantomData));
+    if let Some(error) = error {
+        handle_device_error(device, &error);
+        None
+    } else {
+        Some(id)
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderSetPipeline(
+    bundle_encoder: id::RenderBundleEncoderId,
+    pipeline_id: id::RenderPipelineId,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_set_pipeline(bundle_encoder, pipeline_id);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderSetBindGroup(
+    bundle_encoder: id::RenderBundleEncoderId,
+    group_index: u32,
+    group: id::BindGroupId,
+    dynamic_offset_count: u32,
+    dynamic_offsets: *const u32,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_set_bind_group(
+        bundle_encoder,
+        group_index,
+        group,
+        dynamic_offsets,
+        dynamic_offset_count as usize,
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderSetIndexBuffer(
+    bundle_encoder: id::RenderBundleEncoderId,
+    buffer: id::BufferId,
+    index_format: native::WGPUIndexFormat,
+    offset: u64,
+    size: u64,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_encoder.set_index_buffer(
+        buffer,
+        conv::map_index_format(index_format).expect("Index format cannot be undefined"),
+        offset,
+        NonZeroU64::new(size),
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderSetVertexBuffer(
+    bundle_encoder: id::RenderBundleEncoderId,
+    slot: u32,
+    buffer: id::BufferId,
+    offset: u64,
+    size: u64,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_set_vertex_buffer(
+        bundle_encoder,
+        slot,
+        buffer,
+        offset,
+        NonZeroU64::new(size),
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderDraw(
+    bundle_encoder: id::RenderBundleEncoderId,
+    vertex_count: u32,
+    instance_count: u32,
+    first_vertex: u32,
+    first_instance: u32,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_draw(
+        bundle_encoder,
+        vertex_count,
+        instance_count,
+        first_vertex,
+        first_instance,
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderDrawIndexed(
+    bundle_encoder: id::RenderBundleEncoderId,
+    index_count: u32,
+    instance_count: u32,
+    first_index: u32,
+    base_vertex: i32,
+    first_instance: u32,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_draw_indexed(
+        bundle_encoder,
+        index_count,
+        instance_count,
+        first_index,
+        base_vertex,
+        first_instance,
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderDrawIndirect(
+    bundle_encoder: id::RenderBundleEncoderId,
+    buffer: id::BufferId,
+    indirect_offset: u64,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_draw_indirect(bundle_encoder, buffer, indirect_offset);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderDrawIndexedIndirect(
+    bundle_encoder: id::RenderBundleEncoderId,
+    buffer: id::BufferId,
+    indirect_offset: u64,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_draw_indexed_indirect(bundle_encoder, buffer, indirect_offset);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderInsertDebugMarker(
+    bundle_encoder: id::RenderBundleEncoderId,
+    marker_label: *const std::os::raw::c_char,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_insert_debug_marker(bundle_encoder, marker_label);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderPushDebugGroup(
+    bundle_encoder: id::RenderBundleEncoderId,
+    group_label: *const std::os::raw::c_char,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_push_debug_group(bundle_encoder, group_label);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuRenderBundleEncoderPopDebugGroup(
+    bundle_encoder: id::RenderBundleEncoderId,
+) {
+    let bundle_encoder = bundle_encoder
+        .as_mut()
+        .expect("Render bundle encoder invalid");
+    bundle_ffi::wgpu_render_bundle_pop_debug_group(bundle_encoder);
+}
@@ -1,6 +1,11 @@
-use crate::{make_slice, map_enum, native, Label, OwnedLabel};
+use crate::{follow_chain, make_slice, map_enum, native, Label, OwnedLabel};
 use naga;
-use std::{borrow::Cow, ffi::CStr, num::NonZeroU32};
+use std::{
+    borrow::Cow,
+    convert::TryInto,
+    ffi::CStr,
+    num::{NonZeroU32, NonZeroU8},
+};
 use wgc::{id, pipeline::ShaderModuleSource};
 
 map_enum!(
@@ -26,7 +31,17 @@ map_enum!(
     "Unknown address mode",
     ClampToEdge,
     Repeat,
-    MirrorRepeat
+    MirrorRepeat,
+    ClampToBorder
+);
+map_enum!(
+    map_sampler_border_color,
+    WGPUSamplerBorderColor,
+    wgt::SamplerBorderColor,
+    "Unknown border color",
+    TransparentBlack,
+    OpaqueBlack,
+    OpaqueWhite
 );
 map_enum!(
     map_filter_mode,
@@ -163,6 +178,25 @@ map_enum!(
     Sint32x3,
     Sint32x4
 );
+map_enum!(
+    map_pipeline_statistic_name,
+    WGPUPipelineStatisticName,
+    wgt::PipelineStatisticsTypes,
+    VertexShaderInvocations: VERTEX_SHADER_INVOCATIONS,
+    ClipperInvocations: CLIPPER_INVOCATIONS,
+    ClipperPrimitivesOut: CLIPPER_PRIMITIVES_OUT,
+    FragmentShaderInvocations: FRAGMENT_SHADER_INVOCATIONS,
+    ComputeShaderInvocations: COMPUTE_SHADER_INVOCATIONS
+);
+map_enum!(
+    map_feature,
+    WGPUFeatureName,
+    wgt::Features,
+    DepthClamping: DEPTH_CLIP_CONTROL,
+    TimestampQuery: TIMESTAMP_QUERY,
+    PipelineStatisticsQuery: PIPELINE_STATISTICS_QUERY,
+    TextureCompressionBC: TEXTURE_COMPRESSION_BC
+);
 
 pub fn map_extent3d(native: &native::WGPUExtent3D) -> wgt::Extent3d {
     wgt::Extent3d {
@@ -180,6 +214,17 @@ pub fn map_origin3d(native: &native::WGPUOrigin3D) -> wgt::Origin3d {
     }
 }
 
+pub fn map_instance_descriptor(
+    _: &native::WGPUInstanceDescriptor,
+    extras: Option<&native::WGPUInstanceExtras>,
+) -> wgt::Backends {
+    // Unknown bits are ignored.
+    match extras.map(|extras| extras.backends) {
+        None | Some(0) => wgt::Backends::PRIMARY,
+        Some(x) => wgt::Backends::from_bits_truncate(x),
+    }
+}
+
 pub fn map_adapter_options<'a>(
     options: &native::WGPURequestAdapterOptions,
     extras: Option<&native::WGPUAdapterExtras>,
@@ -194,10 +239,19 @@ pub fn map_adapter_options<'a>(
 pub fn map_device_descriptor<'a>(
     des: &native::WGPUDeviceDescriptor,
     extras: Option<&native::WGPUDeviceExtras>,
-) -> (wgt::DeviceDescriptor<Label<'a>>, Option<String>) {
-    let native_limits = unsafe { (*des.requiredLimits).limits };
+) -> Result<(wgt::DeviceDescriptor<Label<'a>>, Option<String>), native::WGPUFeatureName> {
+    let limits = unsafe { des.requiredLimits.as_ref() }
+        .map(|required_limits| unsafe {
+            follow_chain!(
+                map_required_limits(required_limits,
+                WGPUSType_RequiredLimitsExtras => native::WGPURequiredLimitsExtras)
+            )
+        })
+        .unwrap_or_default();
     let mut features = wgt::Features::empty();
-    let limits = map_limits(native_limits);
+    for feature in unsafe { make_slice(des.requiredFeatures, des.requiredFeaturesCount as usize) } {
+        features |= map_feature(*feature)?;
+    }
     if let Some(extras) = extras {
         // Handle native features speficied in extras
         if (extras.nativeFeatures
@@ -206,24 +260,69 @@ pub fn map_device_descriptor<'a>(
         {
             features |= wgt::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
         }
-        return (
+        if (extras.nativeFeatures & native::WGPUNativeFeature_PUSH_CONSTANTS) > 0 {
+            features |= wgt::Features::PUSH_CONSTANTS;
+        }
+        if (extras.nativeFeatures & native::WGPUNativeFeature_MULTI_DRAW_INDIRECT_COUNT) > 0 {
+            features |= wgt::Features::MULTI_DRAW_INDIRECT_COUNT;
+        }
+        if (extras.nativeFeatures & native::WGPUNativeFeature_CLEAR_COMMANDS) > 0 {
+            features |= wgt::Features::CLEAR_COMMANDS;
+        }
+        if (extras.nativeFeatures & native::WGPUNativeFeature_ADDRESS_MODE_CLAMP_TO_BORDER) > 0 {
+            features |= wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER;
+        }
+        if (extras.nativeFeatures & native::WGPUNativeFeature_TEXTURE_BINDING_ARRAY) > 0 {
+            features |= wgt::Features::TEXTURE_BINDING_ARRAY;
+        }
+        return Ok((
             wgt::DeviceDescriptor {
                 label: OwnedLabel::new(extras.label).into_cow(),
                 features: features,
                 limits,
             },
             OwnedLabel::new(extras.tracePath).into_inner(),
-        );
+        ));
     } else {
-        return (
+        return Ok((
             wgt::DeviceDescriptor {
                 label: None,
                 features,
-                limits: wgt::Limits::default(),
+                limits,
             },
             None,
-        );
+        ));
+    }
+}
+
+pub fn map_required_limits(
+    required_limits: &native::WGPURequiredLimits,
+    extras: Option<&native::WGPURequiredLimitsExtras>,
+) -> wgt::Limits {
+    let mut wgt_limits = map_limits(required_limits.limits);
+    if let Some(extras) = extras {
+        if extras.maxPushConstantSize != 0 {
+            wgt_limits.max_push_constant_size = extras.maxPushConstantSize;
+        }
     }
+    wgt_limits
+}
+
+pub fn features_to_native(features: wgt::Features) -> Vec<native::WGPUFeatureName> {
+    let mut temp = Vec::new();
+    if features.contains(wgt::Features::DEPTH_CLIP_CONTROL) {
+        temp.push(native::WGPUFeatureName_DepthClamping);
+    }
+    if features.contains(wgt::Features::TIMESTAMP_QUERY) {
+        temp.push(native::WGPUFeatureName_TimestampQuery);
+    }
+    if features.contains(wgt::Features::PIPELINE_STATISTICS_QUERY) {
+        temp.push(native::WGPUFeatureName_PipelineStatisticsQuery);
+    }
+    if features.contains(wgt::Features::TEXTURE_COMPRESSION_BC) {
+        temp.push(native::WGPUFeatureName_TextureCompressionBC);
+    }
+    temp
 }
 
 pub fn map_limits(limits: native::WGPULimits) -> wgt::Limits {
@@ -272,58 +371,122 @@ pub fn map_limits(limits: native::WGPULimits) -> wgt::Limits {
     if limits.maxStorageBufferBindingSize != 0 {
         wgt_limits.max_storage_buffer_binding_size = limits.maxStorageBufferBindingSize as u32;
     }
-    /* not yet available in wgpu-core
     if limits.minUniformBufferOffsetAlignment != 0 {
-        wgt_limits.yyyy = limits.minUniformBufferOffsetAlignment;
+        wgt_limits.min_uniform_buffer_offset_alignment = limits.minUniformBufferOffsetAlignment;
     }
     if limits.minStorageBufferOffsetAlignment != 0 {
-        wgt_limits.yyyy = limits.minStorageBufferOffsetAlignment;
+        wgt_limits.min_storage_buffer_offset_alignment = limits.minStorageBufferOffsetAlignment;
     }
-    */
     if limits.maxVertexBuffers != 0 {
         wgt_limits.max_vertex_buffers = limits.maxVertexBuffers;
     }
     if limits.maxVertexAttributes != 0 {
         wgt_limits.max_vertex_attributes = limits.maxVertexAttributes;
     }
-    /* not yet available in wgpu-core
     if limits.maxVertexBufferArrayStride != 0 {
-        wgt_limits.yyyy = limits.maxVertexBufferArrayStride;
+        wgt_limits.max_vertex_buffer_array_stride = limits.maxVertexBufferArrayStride;
     }
     if limits.maxInterStageShaderComponents != 0 {
-        wgt_limits.yyyy = limits.maxInterStageShaderComponents;
+        wgt_limits.max_inter_stage_shader_components = limits.maxInterStageShaderComponents;
     }
     if limits.maxComputeWorkgroupStorageSize != 0 {
-        wgt_limits.yyyy = limits.maxComputeWorkgroupStorageSize;
+        wgt_limits.max_compute_workgroup_storage_size = limits.maxComputeWorkgroupStorageSize;
     }
     if limits.maxComputeInvocationsPerWorkgroup != 0 {
-        wgt_limits.yyyy = limits.maxComputeInvocationsPerWorkgroup;
+        wgt_limits.max_compute_invocations_per_workgroup = limits.maxComputeInvocationsPerWorkgroup;
     }
     if limits.maxComputeWorkgroupSizeX != 0 {
-        wgt_limits.yyyy = limits.maxComputeWorkgroupSizeX;
+        wgt_limits.max_compute_workgroup_size_x = limits.maxComputeWorkgroupSizeX;
     }
     if limits.maxComputeWorkgroupSizeY != 0 {
-        wgt_limits.yyyy = limits.maxComputeWorkgroupSizeY;
+        wgt_limits.max_compute_workgroup_size_y = limits.maxComputeWorkgroupSizeY;
     }
     if limits.maxComputeWorkgroupSizeZ != 0 {
-        wgt_limits.yyyy = limits.maxComputeWorkgroupSizeZ;
+        wgt_limits.max_compute_workgroup_size_z = limits.maxComputeWorkgroupSizeZ;
     }
     if limits.maxComputeWorkgroupsPerDimension != 0 {
-        wgt_limits.yyyy = limits.maxComputeWorkgroupsPerDimension;
+        wgt_limits.max_compute_workgroups_per_dimension = limits.maxComputeWorkgroupsPerDimension;
     }
-    */
     return wgt_limits;
 }
 
+pub unsafe fn map_pipeline_layout_descriptor<'a>(
+    des: &native::WGPUPipelineLayoutDescriptor,
+    extras: Option<&native::WGPUPipelineLayoutExtras>,
+) -> wgc::binding_model::PipelineLayoutDescriptor<'a> {
+    let push_constant_ranges = extras
+        .map(|extras| {
+            make_slice(
+                extras.pushConstantRanges,
+                extras.pushConstantRangeCount as usize,
+            )
+            .iter()
+            .map(|range| wgt::PushConstantRange {
+                stages: wgt::ShaderStages::from_bits(range.stages).expect("Invalid shader stage"),
+                range: range.start..range.end,
+            })
+            .collect()
+        })
+        .unwrap_or_default();
+    wgc::binding_model::PipelineLayoutDescriptor {
+        label: OwnedLabel::new(des.label).into_cow(),
+        bind_group_layouts: Cow::Borrowed(make_slice(
+            des.bindGroupLayouts,
+            des.bindGroupLayoutCount as usize,
+        )),
+        push_constant_ranges: Cow::Owned(push_constant_ranges),
+    }
+}
+
+pub fn map_bind_group_layout_entry_count(
+    _: &native::WGPUBindGroupLayoutEntry,
+    extras: Option<&native::WGPUBindGroupLayoutEntryExtras>,
+) -> Option<NonZeroU32> {
+    extras.and_then(|extras| NonZeroU32::new(extras.count))
+}
+
+pub unsafe fn map_bind_group_entry_texture_views<'a>(
+    _: &native::WGPUBindGroupEntry,
+    extras: Option<&native::WGPUBindGroupEntryExtras>,
+) -> Option<&'a [id::TextureViewId]> {
+    extras.map(|extras| make_slice(extras.textureViews, extras.textureViewCount as usize))
+}
+
+pub fn map_sampler_descriptor<'a>(
+    des: &native::WGPUSamplerDescriptor,
+    extras: Option<&native::WGPUSamplerDescriptorExtras>,
+) -> wgc::resource::SamplerDescriptor<'a> {
+    wgc::resource::SamplerDescriptor {
+        label: OwnedLabel::new(des.label).into_cow(),
+        address_modes: [
+            map_address_mode(des.addressModeU),
+            map_address_mode(des.addressModeV),
+            map_address_mode(des.addressModeW),
+        ],
+        mag_filter: map_filter_mode(des.magFilter),
+        min_filter: map_filter_mode(des.minFilter),
+        mipmap_filter: map_filter_mode(des.mipmapFilter),
+        lod_min_clamp: des.lodMinClamp,
+        lod_max_clamp: des.lodMaxClamp,
+        compare: map_compare_function(des.compare).ok(),
+        // Out of range values are saturated rather than dropped so that wgpu-core
+        // rejects them instead of silently disabling anisotropic filtering.
+        anisotropy_clamp: NonZeroU8::new(des.maxAnisotropy.try_into().unwrap_or(u8::MAX)),
+        // Only sampled with ClampToBorder, which wgpuDeviceCreateSampler rejects
+        // when no border color is given.
+        border_color: extras.map(|extras| map_sampler_border_color(extras.borderColor)),
+    }
+}
+
 pub fn map_shader_module<'a>(
     _: &native::WGPUShaderModuleDescriptor,
     spirv: Option<&native::WGPUShaderModuleSPIRVDescriptor>,
     wgsl: Option<&native::WGPUShaderModuleWGSLDescriptor>,
-) -> ShaderModuleSource<'a> {
+) -> Result<ShaderModuleSource<'a>, naga::front::spv::Error> {
     if let Some(wgsl) = wgsl {
         let c_str: &CStr = unsafe { CStr::from_ptr(wgsl.code) };
         let str_slice: &str = c_str.to_str().expect("not a valid utf-8 string");
-        ShaderModuleSource::Wgsl(Cow::Borrowed(str_slice))
+        Ok(ShaderModuleSource::Wgsl(Cow::Borrowed(str_slice)))
     } else if let Some(spirv) = spirv {
         let slice = unsafe { make_slice(spirv.code, spirv.codeSize as usize) };
         // Parse the given shader code and store its representation.
@@ -332,14 +495,28 @@ pub fn map_shader_module<'a>(
             strict_capabilities: true,
             block_ctx_dump_prefix: None,
         };
+        // A bad magic number, truncated word stream or out of bounds operand is
+        // reported by the parser instead of reaching the driver.
         let parser = naga::front::spv::Parser::new(slice.iter().cloned(), &options);
-        let module = parser.parse().unwrap();
-        ShaderModuleSource::Naga(module)
+        let module = parser.parse()?;
+        Ok(ShaderModuleSource::Naga(module))
     } else {
         panic!("Shader not provided.");
     }
 }
 
+pub fn map_image_subresource_range(
+    native: &native::WGPUImageSubresourceRange,
+) -> wgt::ImageSubresourceRange {
+    wgt::ImageSubresourceRange {
+        aspect: map_texture_aspect(native.aspect),
+        base_mip_level: native.baseMipLevel,
+        mip_level_count: NonZeroU32::new(native.mipLevelCount),
+        base_array_layer: native.baseArrayLayer,
+        array_layer_count: NonZeroU32::new(native.arrayLayerCount),
+    }
+}
+
 pub fn map_image_copy_texture(
     native: &native::WGPUImageCopyTexture,
 ) -> wgc::command::ImageCopyTexture {
@@ -368,6 +545,17 @@ pub fn map_texture_data_layout(native: &native::WGPUTextureDataLayout) -> wgt::I
     }
 }
 
+/// Computes `bytes_per_row` for a buffer-texture copy of `width` texels, padded to
+/// `COPY_BYTES_PER_ROW_ALIGNMENT` as required by wgpu-core. Returns `None` if the
+/// result doesn't fit in a `u32`.
+pub fn aligned_bytes_per_row(format: wgt::TextureFormat, width: u32) -> Option<u32> {
+    let info = format.describe();
+    let block_width = info.block_dimensions.0 as u64;
+    let unpadded = (width as u64 + block_width - 1) / block_width * info.block_size as u64;
+    let align = wgt::COPY_BYTES_PER_ROW_ALIGNMENT as u64;
+    ((unpadded + align - 1) / align * align).try_into().ok()
+}
+
 pub fn map_color(native: &native::WGPUColor) -> wgt::Color {
     wgt::Color {
         r: native.r,
@@ -401,7 +589,6 @@ pub fn map_texture_dimension(value: native::WGPUTextureDimension) -> wgt::Textur
 }
 
 pub fn map_texture_format(value: native::WGPUTextureFormat) -> Option<wgt::TextureFormat> {
-    // TODO: Add support for BC formats
     match value {
         native::WGPUTextureFormat_R8Unorm => Some(wgt::TextureFormat::R8Unorm),
         native::WGPUTextureFormat_R8Snorm => Some(wgt::TextureFormat::R8Snorm),
@@ -442,6 +629,20 @@ pub fn map_texture_format(value: native::WGPUTextureFormat) -> Option<wgt::Textu
         native::WGPUTextureFormat_Depth24PlusStencil8 => {
             Some(wgt::TextureFormat::Depth24PlusStencil8)
         }
+        native::WGPUTextureFormat_BC1RGBAUnorm => Some(wgt::TextureFormat::Bc1RgbaUnorm),
+        native::WGPUTextureFormat_BC1RGBAUnormSrgb => Some(wgt::TextureFormat::Bc1RgbaUnormSrgb),
+        native::WGPUTextureFormat_BC2RGBAUnorm => Some(wgt::TextureFormat::Bc2RgbaUnorm),
+        native::WGPUTextureFormat_BC2RGBAUnormSrgb => Some(wgt::TextureFormat::Bc2RgbaUnormSrgb),
+        native::WGPUTextureFormat_BC3RGBAUnorm => Some(wgt::TextureFormat::Bc3RgbaUnorm),
+        native::WGPUTextureFormat_BC3RGBAUnormSrgb => Some(wgt::TextureFormat::Bc3RgbaUnormSrgb),
+        native::WGPUTextureFormat_BC4RUnorm => Some(wgt::TextureFormat::Bc4RUnorm),
+        native::WGPUTextureFormat_BC4RSnorm => Some(wgt::TextureFormat::Bc4RSnorm),
+        native::WGPUTextureFormat_BC5RGUnorm => Some(wgt::TextureFormat::Bc5RgUnorm),
+        native::WGPUTextureFormat_BC5RGSnorm => Some(wgt::TextureFormat::Bc5RgSnorm),
+        native::WGPUTextureFormat_BC6HRGBUfloat => Some(wgt::TextureFormat::Bc6hRgbUfloat),
+        native::WGPUTextureFormat_BC6HRGBFloat => Some(wgt::TextureFormat::Bc6hRgbSfloat),
+        native::WGPUTextureFormat_BC7RGBAUnorm => Some(wgt::TextureFormat::Bc7RgbaUnorm),
+        native::WGPUTextureFormat_BC7RGBAUnormSrgb => Some(wgt::TextureFormat::Bc7RgbaUnormSrgb),
         _ => None,
     }
 }
@@ -485,10 +686,60 @@ pub fn to_native_texture_format(rs_type: wgt::TextureFormat) -> native::WGPUText
         wgt::TextureFormat::Depth32Float => native::WGPUTextureFormat_Depth32Float,
         wgt::TextureFormat::Depth24Plus => native::WGPUTextureFormat_Depth24Plus,
         wgt::TextureFormat::Depth24PlusStencil8 => native::WGPUTextureFormat_Depth24PlusStencil8,
+        wgt::TextureFormat::Bc1RgbaUnorm => native::WGPUTextureFormat_BC1RGBAUnorm,
+        wgt::TextureFormat::Bc1RgbaUnormSrgb => native::WGPUTextureFormat_BC1RGBAUnormSrgb,
+        wgt::TextureFormat::Bc2RgbaUnorm => native::WGPUTextureFormat_BC2RGBAUnorm,
+        wgt::TextureFormat::Bc2RgbaUnormSrgb => native::WGPUTextureFormat_BC2RGBAUnormSrgb,
+        wgt::TextureFormat::Bc3RgbaUnorm => native::WGPUTextureFormat_BC3RGBAUnorm,
+        wgt::TextureFormat::Bc3RgbaUnormSrgb => native::WGPUTextureFormat_BC3RGBAUnormSrgb,
+        wgt::TextureFormat::Bc4RUnorm => native::WGPUTextureFormat_BC4RUnorm,
+        wgt::TextureFormat::Bc4RSnorm => native::WGPUTextureFormat_BC4RSnorm,
+        wgt::TextureFormat::Bc5RgUnorm => native::WGPUTextureFormat_BC5RGUnorm,
+        wgt::TextureFormat::Bc5RgSnorm => native::WGPUTextureFormat_BC5RGSnorm,
+        wgt::TextureFormat::Bc6hRgbUfloat => native::WGPUTextureFormat_BC6HRGBUfloat,
+        wgt::TextureFormat::Bc6hRgbSfloat => native::WGPUTextureFormat_BC6HRGBFloat,
+        wgt::TextureFormat::Bc7RgbaUnorm => native::WGPUTextureFormat_BC7RGBAUnorm,
+        wgt::TextureFormat::Bc7RgbaUnormSrgb => native::WGPUTextureFormat_BC7RGBAUnormSrgb,
         _ => unimplemented!(),
     }
 }
 
+pub fn to_native_swap_chain_status(status: wgt::SurfaceStatus) -> native::WGPUSwapChainStatus {
+    match status {
+        wgt::SurfaceStatus::Good => native::WGPUSwapChainStatus_Good,
+        wgt::SurfaceStatus::Suboptimal => native::WGPUSwapChainStatus_Suboptimal,
+        wgt::SurfaceStatus::Timeout => native::WGPUSwapChainStatus_Timeout,
+        wgt::SurfaceStatus::Outdated => native::WGPUSwapChainStatus_Outdated,
+        wgt::SurfaceStatus::Lost => native::WGPUSwapChainStatus_Lost,
+    }
+}
+
+pub fn map_primitive_state(
+    native: &native::WGPUPrimitiveState,
+    depth_clamping: Option<&native::WGPUPrimitiveDepthClampingState>,
+) -> wgt::PrimitiveState {
+    wgt::PrimitiveState {
+        topology: map_primitive_topology(native.topology),
+        strip_index_format: map_index_format(native.stripIndexFormat).ok(),
+        front_face: match native.frontFace {
+            native::WGPUFrontFace_CCW => wgt::FrontFace::Ccw,
+            native::WGPUFrontFace_CW => wgt::FrontFace::Cw,
+            _ => panic!("Front face not provided"),
+        },
+        cull_mode: match native.cullMode {
+            native::WGPUCullMode_Front => Some(wgt::Face::Front),
+            native::WGPUCullMode_Back => Some(wgt::Face::Back),
+            _ => None,
+        },
+        // Requires the DepthClamping feature, wgpu-core rejects the pipeline otherwise.
+        unclipped_depth: depth_clamping
+            .map(|state| state.clampDepth)
+            .unwrap_or(false),
+        polygon_mode: wgt::PolygonMode::Fill,
+        conservative: false,
+    }
+}
+
 pub fn map_stencil_face_state(value: native::WGPUStencilFaceState) -> wgt::StencilFaceState {
     wgt::StencilFaceState {
         compare: map_compare_function(value.compare).unwrap(),
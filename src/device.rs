@@ -1,14 +1,21 @@
-use crate::conv::{map_adapter_options, map_device_descriptor, map_shader_module};
-use crate::{conv, follow_chain, handle_device_error, make_slice, native, OwnedLabel, GLOBAL};
+use crate::conv::{
+    map_adapter_options, map_bind_group_entry_texture_views, map_bind_group_layout_entry_count,
+    map_device_descriptor, map_pipeline_layout_descriptor, map_primitive_state,
+    map_sampler_descriptor, map_shader_module,
+};
+use crate::{
+    clear_error_scopes, command, conv, follow_chain, handle_device_error, handle_device_error_raw,
+    make_slice, native, OwnedLabel, GLOBAL,
+};
 use lazy_static::lazy_static;
 use std::{
     borrow::Cow,
     collections::HashMap,
-    convert::TryInto,
+    ffi::CString,
     marker::PhantomData,
-    num::{NonZeroU32, NonZeroU64, NonZeroU8},
+    num::{NonZeroU32, NonZeroU64},
     path::Path,
-    sync::Mutex,
+    sync::{Arc, Mutex},
 };
 use thiserror::Error;
 use wgc::{gfx_select, id};
@@ -53,6 +60,29 @@ pub unsafe extern "C" fn wgpuInstanceRequestAdapter(
     (callback.unwrap())(status, adapter_id, message_ptr, userdata);
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wgpuInstanceEnumerateAdapters(
+    _: native::WGPUInstance,
+    backends: native::WGPUInstanceBackendFlags,
+    callback: native::WGPUEnumerateAdaptersCallback,
+    userdata: *mut std::os::raw::c_void,
+) {
+    // An empty mask means no filtering, like WGPUBackendType_Null in WGPUAdapterExtras.
+    // Unknown bits are ignored.
+    let backend_bits = match backends {
+        0 => wgt::Backends::all(),
+        x => wgt::Backends::from_bits_truncate(x),
+    };
+    let callback = callback.expect("Callback cannot be null");
+    let adapters = GLOBAL
+        .enumerate_adapters(wgc::instance::AdapterInputs::Mask(backend_bits, |_| {
+            PhantomData
+        }));
+    for adapter_id in adapters {
+        callback(adapter_id, userdata);
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wgpuAdapterRequestDevice(
     adapter: id::AdapterId,
@@ -60,21 +90,47 @@ pub unsafe extern "C" fn wgpuAdapterRequestDevice(
     callback: native::WGPURequestDeviceCallback,
     userdata: *mut ::std::os::raw::c_void,
 ) {
-    let (desc, trace_str) = follow_chain!(
+    let (desc, trace_str) = match follow_chain!(
         map_device_descriptor(descriptor,
         WGPUSType_DeviceExtras => native::WGPUDeviceExtras)
-    );
+    ) {
+        Ok(mapped) => mapped,
+        Err(feature) => {
+            let message = CString::new(format!("Unknown feature: {}", feature)).unwrap();
+            (callback.unwrap())(
+                native::WGPURequestDeviceStatus_Error,
+                None,
+                message.as_ptr(),
+                userdata,
+            );
+            return;
+        }
+    };
     let trace_path = trace_str.as_ref().map(|path| Path::new(path));
 
     let (id, error) = gfx_select!(adapter => GLOBAL.adapter_request_device(adapter, &desc, trace_path, PhantomData));
 
-    let status = match error {
-        Some(_error) => native::WGPURequestDeviceStatus_Error,
-        None => native::WGPURequestDeviceStatus_Success,
-    };
-
-    let message_ptr = std::ptr::null();
-    (callback.unwrap())(status, id, message_ptr, userdata);
+    // Requesting limits or features the adapter can't provide fails here; the
+    // message tells the caller which one.
+    match error {
+        Some(error) => {
+            let message = CString::new(format!("{:?}", error)).unwrap();
+            (callback.unwrap())(
+                native::WGPURequestDeviceStatus_Error,
+                None,
+                message.as_ptr(),
+                userdata,
+            );
+        }
+        None => {
+            (callback.unwrap())(
+                native::WGPURequestDeviceStatus_Success,
+                Some(id),
+                std::ptr::null(),
+                userdata,
+            );
+        }
+    }
 }
 
 #[no_mangle]
@@ -85,9 +141,11 @@ pub unsafe extern "C" fn wgpuAdapterGetProperties(
     let maybe_props = gfx_select!(adapter => GLOBAL.adapter_get_info(adapter));
     match maybe_props {
         Ok(props) => {
-            //let name = std::ffi::CString::new(props.name);
-            //store_object_globally(name); --> how?
-            //properties.name = c_string.unwrap(name).as_ptr();
+            // Owned by the caller, released with wgpuAdapterPropertiesFreeMembers.
+            properties.name = std::ffi::CString::new(props.name)
+                .unwrap_or_default()
+                .into_raw();
+            properties.driverDescription = std::ptr::null();
             properties.vendorID = props.vendor as u32;
             properties.deviceID = props.device as u32;
             properties.adapterType = match props.device_type {
@@ -111,6 +169,68 @@ pub unsafe extern "C" fn wgpuAdapterGetProperties(
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wgpuAdapterPropertiesFreeMembers(
+    properties: native::WGPUAdapterProperties,
+) {
+    if !properties.name.is_null() {
+        drop(std::ffi::CString::from_raw(properties.name as *mut _));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuAdapterEnumerateFeatures(
+    adapter: id::AdapterId,
+    features: *mut native::WGPUFeatureName,
+) -> usize {
+    let adapter_features =
+        gfx_select!(adapter => GLOBAL.adapter_features(adapter)).expect("Invalid adapter");
+    write_features(adapter_features, features)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuAdapterHasFeature(
+    adapter: id::AdapterId,
+    feature: native::WGPUFeatureName,
+) -> bool {
+    let adapter_features =
+        gfx_select!(adapter => GLOBAL.adapter_features(adapter)).expect("Invalid adapter");
+    conv::map_feature(feature).map_or(false, |feature| adapter_features.contains(feature))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuDeviceEnumerateFeatures(
+    device: id::DeviceId,
+    features: *mut native::WGPUFeatureName,
+) -> usize {
+    let device_features =
+        gfx_select!(device => GLOBAL.device_features(device)).expect("Invalid device");
+    write_features(device_features, features)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuDeviceHasFeature(
+    device: id::DeviceId,
+    feature: native::WGPUFeatureName,
+) -> bool {
+    let device_features =
+        gfx_select!(device => GLOBAL.device_features(device)).expect("Invalid device");
+    conv::map_feature(feature).map_or(false, |feature| device_features.contains(feature))
+}
+
+// Follows the webgpu.h convention: call once with NULL to get the count, then again
+// with an array of that size.
+unsafe fn write_features(
+    wgt_features: wgt::Features,
+    features: *mut native::WGPUFeatureName,
+) -> usize {
+    let temp = conv::features_to_native(wgt_features);
+    if !features.is_null() {
+        std::ptr::copy_nonoverlapping(temp.as_ptr(), features, temp.len());
+    }
+    temp.len()
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wgpuAdapterGetLimits(
     adapter: id::AdapterId,
@@ -121,7 +241,7 @@ pub unsafe extern "C" fn wgpuAdapterGetLimits(
         Ok(wgt_limits) => write_limits_struct(wgt_limits, limits),
         _ => panic!("Calling wgpuAdapterGetLimits() on an invalid adapter."),
     }
-    return false; // todo: what is the purpose of this return value?
+    return true;
 }
 
 #[no_mangle]
@@ -134,7 +254,7 @@ pub unsafe extern "C" fn wgpuDeviceGetLimits(
         Ok(wgt_limits) => write_limits_struct(wgt_limits, limits),
         _ => panic!("Calling wgpuDeviceGetLimits() on an invalid device."),
     }
-    return false;
+    return true;
 }
 
 fn write_limits_struct(
@@ -179,11 +299,17 @@ pub unsafe extern "C" fn wgpuDeviceCreateShaderModule(
     descriptor: &native::WGPUShaderModuleDescriptor,
 ) -> Option<id::ShaderModuleId> {
     let label = OwnedLabel::new(descriptor.label);
-    let source = follow_chain!(
+    let source = match follow_chain!(
         map_shader_module(descriptor,
         WGPUSType_ShaderModuleSPIRVDescriptor => native::WGPUShaderModuleSPIRVDescriptor,
         WGPUSType_ShaderModuleWGSLDescriptor => native::WGPUShaderModuleWGSLDescriptor)
-    );
+    ) {
+        Ok(source) => source,
+        Err(error) => {
+            handle_device_error(device, &error);
+            return None;
+        }
+    };
 
     let desc = wgc::pipeline::ShaderModuleDescriptor {
         label: label.as_cow(),
@@ -219,10 +345,16 @@ pub extern "C" fn wgpuDeviceCreateBuffer(
         handle_device_error(device, &error);
         None
     } else {
+        BUFFER_TO_DEVICE.lock().unwrap().insert(id, device);
         Some(id)
     }
 }
 
+lazy_static! {
+    static ref BUFFER_TO_DEVICE: Mutex<HashMap<id::BufferId, id::DeviceId>> =
+        Mutex::new(HashMap::new());
+}
+
 #[no_mangle]
 pub extern "C" fn wgpuBufferDestroy(buffer_id: id::BufferId) {
     gfx_select!(buffer_id => GLOBAL.buffer_destroy(buffer_id)).expect("Unable to destroy buffer");
@@ -327,7 +459,12 @@ pub unsafe extern "C" fn wgpuDeviceCreateBindGroupLayout(
             ty,
             binding: entry.binding,
             visibility: wgt::ShaderStages::from_bits(entry.visibility).unwrap(),
-            count: None, // TODO - What is this?
+            // Binding arrays need TEXTURE_BINDING_ARRAY, and wgpu-core checks the count
+            // against the adapter limits.
+            count: follow_chain!(
+                map_bind_group_layout_entry_count(entry,
+                WGPUSType_BindGroupLayoutEntryExtras => native::WGPUBindGroupLayoutEntryExtras)
+            ),
         });
     }
     let label = OwnedLabel::new(descriptor.label);
@@ -352,7 +489,18 @@ pub unsafe extern "C" fn wgpuDeviceCreateBindGroup(
 ) -> Option<id::BindGroupId> {
     let mut entries = Vec::new();
     for entry in make_slice(descriptor.entries, descriptor.entryCount as usize) {
-        let wgc_entry = if entry.buffer.is_some() {
+        let texture_views = follow_chain!(
+            map_bind_group_entry_texture_views(entry,
+            WGPUSType_BindGroupEntryExtras => native::WGPUBindGroupEntryExtras)
+        );
+        let wgc_entry = if let Some(texture_views) = texture_views {
+            wgc::binding_model::BindGroupEntry {
+                binding: entry.binding,
+                resource: wgc::binding_model::BindingResource::TextureViewArray(Cow::Borrowed(
+                    texture_views,
+                )),
+            }
+        } else if entry.buffer.is_some() {
             wgc::binding_model::BindGroupEntry {
                 binding: entry.binding,
                 resource: wgc::binding_model::BindingResource::Buffer(
@@ -402,14 +550,10 @@ pub unsafe extern "C" fn wgpuDeviceCreatePipelineLayout(
     device: id::DeviceId,
     descriptor: &native::WGPUPipelineLayoutDescriptor,
 ) -> Option<id::PipelineLayoutId> {
-    let desc = wgc::binding_model::PipelineLayoutDescriptor {
-        label: OwnedLabel::new(descriptor.label).into_cow(),
-        bind_group_layouts: Cow::Borrowed(make_slice(
-            descriptor.bindGroupLayouts,
-            descriptor.bindGroupLayoutCount as usize,
-        )),
-        push_constant_ranges: Cow::Borrowed(&[]),
-    };
+    let desc = follow_chain!(
+        map_pipeline_layout_descriptor(descriptor,
+        WGPUSType_PipelineLayoutExtras => native::WGPUPipelineLayoutExtras)
+    );
     let (id, error) =
         gfx_select!(device => GLOBAL.device_create_pipeline_layout(device, &desc, PhantomData));
     if let Some(error) = error {
@@ -459,6 +603,72 @@ pub unsafe extern "C" fn wgpuDeviceCreateCommandEncoder(
     if let Some(error) = error {
         handle_device_error(device, &error);
         None
+    } else {
+        COMMAND_ENCODER_TO_DEVICE.lock().unwrap().insert(id, device);
+        Some(id)
+    }
+}
+
+lazy_static! {
+    static ref COMMAND_ENCODER_TO_DEVICE: Mutex<HashMap<id::CommandEncoderId, id::DeviceId>> =
+        Mutex::new(HashMap::new());
+}
+
+// Command buffers share their id with the encoder they were finished from.
+pub(crate) fn get_device_from_command_encoder(
+    encoder: id::CommandEncoderId,
+) -> Option<id::DeviceId> {
+    COMMAND_ENCODER_TO_DEVICE
+        .lock()
+        .unwrap()
+        .get(&encoder)
+        .copied()
+}
+
+pub(crate) fn handle_command_encoder_error<E: std::any::Any + std::error::Error>(
+    encoder: id::CommandEncoderId,
+    error: &E,
+) {
+    match get_device_from_command_encoder(encoder) {
+        Some(device) => handle_device_error(device, error),
+        None => log::error!("Error on an unknown command encoder: {:?}", error),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuDeviceCreateQuerySet(
+    device: id::DeviceId,
+    descriptor: &native::WGPUQuerySetDescriptor,
+) -> Option<id::QuerySetId> {
+    let ty = match descriptor.type_ {
+        native::WGPUQueryType_Occlusion => wgt::QueryType::Occlusion,
+        native::WGPUQueryType_Timestamp => wgt::QueryType::Timestamp,
+        native::WGPUQueryType_PipelineStatistics => {
+            let mut types = wgt::PipelineStatisticsTypes::empty();
+            for name in make_slice(
+                descriptor.pipelineStatistics,
+                descriptor.pipelineStatisticsCount as usize,
+            ) {
+                types |= conv::map_pipeline_statistic_name(*name)
+                    .expect("Unknown pipeline statistic name");
+            }
+            wgt::QueryType::PipelineStatistics(types)
+        }
+        x => panic!("Unknown query type: {}", x),
+    };
+    let desc = wgt::QuerySetDescriptor {
+        label: OwnedLabel::new(descriptor.label).into_cow(),
+        ty,
+        count: descriptor.count,
+    };
+
+    // Backends without timestamp or pipeline statistics support report a missing
+    // feature here, before any commands referencing the query set are recorded.
+    let (id, error) =
+        gfx_select!(device => GLOBAL.device_create_query_set(device, &desc, PhantomData));
+    if let Some(error) = error {
+        handle_device_error(device, &error);
+        None
     } else {
         Some(id)
     }
@@ -475,9 +685,64 @@ pub unsafe extern "C" fn wgpuQueueSubmit(
     command_count: u32,
     commands: *const id::CommandBufferId,
 ) {
+    // All command buffers go to wgpu-core in one call, which records them into a
+    // single hal submission signaled by one fence value, merging trackers in order.
     let command_buffer_ids = make_slice(commands, command_count as usize);
-    gfx_select!(queue => GLOBAL.queue_submit(queue, command_buffer_ids))
-        .expect("Unable to submit queue")
+    let result = gfx_select!(queue => GLOBAL.queue_submit(queue, command_buffer_ids));
+
+    // Submitted command buffers are consumed and never dropped by the caller.
+    let mut encoder_to_device = COMMAND_ENCODER_TO_DEVICE.lock().unwrap();
+    for command_buffer_id in command_buffer_ids {
+        encoder_to_device.remove(command_buffer_id);
+    }
+    drop(encoder_to_device);
+
+    if let Err(error) = result {
+        handle_device_error(queue, &error);
+    }
+}
+
+struct QueueWorkDoneClosure {
+    callback: native::WGPUQueueWorkDoneCallback,
+    userdata: *mut std::os::raw::c_void,
+}
+
+// wgpu-core's callback only carries user data, so the native callback and its
+// userdata are boxed up and unpacked here once the work is done.
+unsafe extern "C" fn queue_work_done_trampoline(user_data: *mut u8) {
+    let closure = Box::from_raw(user_data as *mut QueueWorkDoneClosure);
+    (closure.callback.unwrap())(native::WGPUQueueWorkDoneStatus_Success, closure.userdata);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuQueueOnSubmittedWorkDone(
+    queue: id::QueueId,
+    _signal_value: u64,
+    callback: native::WGPUQueueWorkDoneCallback,
+    userdata: *mut std::os::raw::c_void,
+) {
+    assert!(callback.is_some(), "Callback cannot be null");
+    let user_data = Box::into_raw(Box::new(QueueWorkDoneClosure { callback, userdata }));
+    let closure = wgc::device::queue::SubmittedWorkDoneClosure {
+        callback: queue_work_done_trampoline,
+        user_data: user_data as *mut u8,
+    };
+
+    // Fires from wgpuDevicePoll once every submission made so far has completed,
+    // in the order the callbacks were registered.
+    if let Err(error) = gfx_select!(queue => GLOBAL.queue_on_submitted_work_done(queue, closure)) {
+        // wgpu-core drops the closure without calling it, reclaim the box here.
+        drop(Box::from_raw(user_data));
+        handle_device_error(queue, &error);
+        (callback.unwrap())(native::WGPUQueueWorkDoneStatus_Error, userdata);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuQueueGetTimestampPeriod(queue: id::QueueId) -> f32 {
+    // Resolved timestamps are raw ticks; multiply by this period to get nanoseconds.
+    gfx_select!(queue => GLOBAL.queue_get_timestamp_period(queue))
+        .expect("Unable to get timestamp period")
 }
 
 #[no_mangle]
@@ -489,8 +754,13 @@ pub unsafe extern "C" fn wgpuQueueWriteBuffer(
     data_size: usize,
 ) {
     let slice = make_slice(data, data_size);
-    gfx_select!(queue => GLOBAL.queue_write_buffer(queue, buffer, buffer_offset, slice))
-        .expect("Unable to write buffer")
+    // wgpu-core stages the upload and checks the range against the buffer size and
+    // COPY_BUFFER_ALIGNMENT. A queue shares its id with its device.
+    if let Err(error) =
+        gfx_select!(queue => GLOBAL.queue_write_buffer(queue, buffer, buffer_offset, slice))
+    {
+        handle_device_error(queue, &error);
+    }
 }
 
 #[no_mangle]
@@ -503,14 +773,15 @@ pub unsafe extern "C" fn wgpuQueueWriteTexture(
     write_size: &native::WGPUExtent3D,
 ) {
     let slice = make_slice(data, data_size);
-    gfx_select!(queue => GLOBAL.queue_write_texture(
+    if let Err(error) = gfx_select!(queue => GLOBAL.queue_write_texture(
         queue,
         &conv::map_image_copy_texture(&destination),
         slice,
         &conv::map_texture_data_layout(&data_layout),
         &conv::map_extent3d(&write_size)
-    ))
-    .expect("Unable to write texture")
+    )) {
+        handle_device_error(queue, &error);
+    }
 }
 
 #[no_mangle]
@@ -522,6 +793,7 @@ pub unsafe extern "C" fn wgpuBufferMapAsync(
     callback: native::WGPUBufferMapCallback,
     user_data: *mut u8,
 ) {
+    let callback = callback.expect("Callback cannot be null");
     let operation = wgc::resource::BufferMapOperation {
         host: match mode as native::WGPUMapMode {
             native::WGPUMapMode_Write => wgc::device::HostMap::Write,
@@ -530,17 +802,83 @@ pub unsafe extern "C" fn wgpuBufferMapAsync(
             x => panic!("Unknown map mode: {}", x),
         },
         // TODO: Change wgpu-core to follow new API
-        callback: std::mem::transmute(callback.expect("Callback cannot be null")),
+        callback: std::mem::transmute(callback),
         user_data,
     };
 
-    gfx_select!(buffer => GLOBAL.buffer_map_async(buffer, offset as u64 .. (offset + size) as u64, operation))
-        .expect("Unable to map buffer")
+    // The mapping resolves on a later wgpuDevicePoll (immediately on the next poll if the
+    // buffer has no pending submission). If it can't even be scheduled, e.g. because the
+    // buffer is already mapped or its device is lost, the callback never fires on its own.
+    if let Err(error) = gfx_select!(buffer => GLOBAL.buffer_map_async(buffer, offset as u64 .. (offset + size) as u64, operation))
+    {
+        log::error!("buffer_map_async() failed: {:?}", error);
+        let status = match error {
+            wgc::resource::BufferAccessError::Device(wgc::device::DeviceError::Lost) => {
+                native::WGPUBufferMapAsyncStatus_DeviceLost
+            }
+            _ => native::WGPUBufferMapAsyncStatus_Error,
+        };
+        callback(status, user_data as _);
+    }
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn wgpuDevicePoll(device: id::DeviceId, force_wait: bool) {
-    gfx_select!(device => GLOBAL.device_poll(device, force_wait)).expect("Unable to poll device")
+    // Polling retires finished submissions, fires pending map callbacks and frees
+    // resources that were only kept alive by in-flight work. Errors (e.g. a lost
+    // device while waiting) are routed to the device callbacks instead of panicking.
+    if let Err(error) = gfx_select!(device => GLOBAL.device_poll(device, force_wait)) {
+        handle_device_error(device, &error);
+    }
+}
+
+// The status is shared with wgpuBufferMapSync, whichever side finishes last frees it.
+unsafe extern "C" fn buffer_map_sync_callback(
+    status: native::WGPUBufferMapAsyncStatus,
+    user_data: *mut std::os::raw::c_void,
+) {
+    let shared = Arc::from_raw(user_data as *const Mutex<native::WGPUBufferMapAsyncStatus>);
+    *shared.lock().unwrap() = status;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuBufferMapSync(
+    buffer: id::BufferId,
+    mode: native::WGPUMapModeFlags,
+    offset: usize,
+    size: usize,
+) -> *mut u8 {
+    let device = match BUFFER_TO_DEVICE.lock().unwrap().get(&buffer).copied() {
+        Some(device) => device,
+        None => {
+            log::error!("Can't map an unknown buffer");
+            return std::ptr::null_mut();
+        }
+    };
+    let shared = Arc::new(Mutex::new(native::WGPUBufferMapAsyncStatus_Unknown));
+    wgpuBufferMapAsync(
+        buffer,
+        mode,
+        offset,
+        size,
+        Some(buffer_map_sync_callback),
+        Arc::into_raw(shared.clone()) as *mut u8,
+    );
+
+    // A mapping that can't be scheduled calls back right away, there is nothing to
+    // wait for. Otherwise waiting blocks on the fence of the last submission using the
+    // buffer, after which wgpu-core resolves the mapping and fires the callback.
+    let pending = *shared.lock().unwrap() == native::WGPUBufferMapAsyncStatus_Unknown;
+    if pending {
+        wgpuDevicePoll(device, true);
+    }
+
+    let status = *shared.lock().unwrap();
+    if status == native::WGPUBufferMapAsyncStatus_Success {
+        wgpuBufferGetMappedRange(buffer, offset, size)
+    } else {
+        std::ptr::null_mut()
+    }
 }
 
 #[no_mangle]
@@ -549,9 +887,28 @@ pub unsafe extern "C" fn wgpuBufferGetMappedRange(
     offset: usize,
     size: usize,
 ) -> *mut u8 {
-    gfx_select!(buffer => GLOBAL.buffer_get_mapped_range(buffer, offset as u64, Some(size as u64)))
-        .expect("Unable to get mapped range")
-        .0
+    // wgpu-core rejects ranges outside of the mapped region and buffers that are
+    // not currently mapped; both hand back a null pointer.
+    match gfx_select!(buffer => GLOBAL.buffer_get_mapped_range(buffer, offset as u64, Some(size as u64)))
+    {
+        Ok((ptr, _)) => ptr,
+        Err(error) => {
+            match BUFFER_TO_DEVICE.lock().unwrap().get(&buffer).copied() {
+                Some(device) => handle_device_error(device, &error),
+                None => log::error!("buffer_get_mapped_range() failed: {:?}", error),
+            }
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuBufferGetConstMappedRange(
+    buffer: id::BufferId,
+    offset: usize,
+    size: usize,
+) -> *const u8 {
+    wgpuBufferGetMappedRange(buffer, offset, size)
 }
 
 #[no_mangle]
@@ -597,23 +954,10 @@ pub unsafe extern "C" fn wgpuDeviceCreateRenderPipeline(
                 .collect(),
             ),
         },
-        primitive: wgt::PrimitiveState {
-            topology: conv::map_primitive_topology(descriptor.primitive.topology),
-            strip_index_format: conv::map_index_format(descriptor.primitive.stripIndexFormat).ok(),
-            front_face: match descriptor.primitive.frontFace {
-                native::WGPUFrontFace_CCW => wgt::FrontFace::Ccw,
-                native::WGPUFrontFace_CW => wgt::FrontFace::Cw,
-                _ => panic!("Front face not provided"),
-            },
-            cull_mode: match descriptor.primitive.cullMode {
-                native::WGPUCullMode_Front => Some(wgt::Face::Front),
-                native::WGPUCullMode_Back => Some(wgt::Face::Back),
-                _ => None,
-            },
-            unclipped_depth: false, // todo: fill this via extras
-            polygon_mode: wgt::PolygonMode::Fill,
-            conservative: false,
-        },
+        primitive: follow_chain!(
+            map_primitive_state(&descriptor.primitive,
+            WGPUSType_PrimitiveDepthClampingState => native::WGPUPrimitiveDepthClampingState)
+        ),
         depth_stencil: descriptor
             .depthStencil
             .as_ref()
@@ -684,18 +1028,18 @@ pub unsafe extern "C" fn wgpuDeviceCreateRenderPipeline(
     }
 }
 
+struct SurfaceData {
+    device: id::DeviceId,
+    config: wgt::SurfaceConfiguration,
+}
+
 lazy_static! {
-    static ref SURFACE_TO_DEVICE: Mutex<HashMap<id::SurfaceId, id::DeviceId>> =
+    static ref SURFACE_DATA: Mutex<HashMap<id::SurfaceId, SurfaceData>> =
         Mutex::new(HashMap::new());
 }
 
 fn get_device_from_surface(surface: id::SurfaceId) -> id::DeviceId {
-    return SURFACE_TO_DEVICE
-        .lock()
-        .unwrap()
-        .get(&surface)
-        .unwrap()
-        .clone();
+    return SURFACE_DATA.lock().unwrap().get(&surface).unwrap().device;
 }
 
 #[no_mangle]
@@ -718,11 +1062,34 @@ pub extern "C" fn wgpuDeviceCreateSwapChain(
         handle_device_error(device, &error);
         None
     } else {
-        SURFACE_TO_DEVICE.lock().unwrap().insert(surface, device);
+        SURFACE_DATA
+            .lock()
+            .unwrap()
+            .insert(surface, SurfaceData { device, config });
         Some(surface) // swap chain_id == surface_id
     }
 }
 
+#[no_mangle]
+pub extern "C" fn wgpuSwapChainResize(swap_chain: id::SurfaceId, width: u32, height: u32) {
+    // Reconfigures the surface after wgpuSwapChainGetCurrentTextureView reported an
+    // outdated or lost swap chain (typically a window resize), keeping the rest of the
+    // descriptor the swap chain was created with.
+    let surface_id = swap_chain;
+    let mut surfaces = SURFACE_DATA.lock().unwrap();
+    let data = surfaces
+        .get_mut(&surface_id)
+        .expect("Swap chain was not created");
+    data.config.width = width;
+    data.config.height = height;
+    let device = data.device;
+    let error = gfx_select!(device => GLOBAL.surface_configure(surface_id, device, &data.config));
+    drop(surfaces);
+    if let Some(error) = error {
+        handle_device_error(device, &error);
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum SurfaceError {
     #[error("Surface timed out")]
@@ -737,32 +1104,51 @@ pub enum SurfaceError {
 pub extern "C" fn wgpuSwapChainGetCurrentTextureView(
     swap_chain: id::SurfaceId,
 ) -> Option<id::TextureViewId> {
-    let surface_id = swap_chain;
+    let device = get_device_from_surface(swap_chain);
+    let (view, status) = get_current_texture_view(swap_chain);
+    match status {
+        Some(wgt::SurfaceStatus::Timeout) => handle_device_error(device, &SurfaceError::Timeout),
+        Some(wgt::SurfaceStatus::Outdated) => handle_device_error(device, &SurfaceError::Outdated),
+        Some(wgt::SurfaceStatus::Lost) => handle_device_error(device, &SurfaceError::Lost),
+        _ => {}
+    }
+    view
+}
+
+#[no_mangle]
+pub extern "C" fn wgpuSwapChainGetCurrentTextureViewWithStatus(
+    swap_chain: id::SurfaceId,
+    status: &mut native::WGPUSwapChainStatus,
+) -> Option<id::TextureViewId> {
+    let (view, surface_status) = get_current_texture_view(swap_chain);
+    // A failure to acquire has already been reported to the device.
+    *status = surface_status.map_or(
+        native::WGPUSwapChainStatus_Lost,
+        conv::to_native_swap_chain_status,
+    );
+    view
+}
+
+// Returns None as the status when acquiring the texture failed, the error is reported
+// to the device.
+fn get_current_texture_view(
+    surface_id: id::SurfaceId,
+) -> (Option<id::TextureViewId>, Option<wgt::SurfaceStatus>) {
     let device = get_device_from_surface(surface_id);
     match gfx_select!(device => GLOBAL.surface_get_current_texture(surface_id, PhantomData)) {
         Err(error) => {
             handle_device_error(device, &error);
-            None
+            (None, None)
         }
         Ok(result) => {
             match result.status {
                 wgt::SurfaceStatus::Good | wgt::SurfaceStatus::Suboptimal => {
                     let texture = result.texture_id.unwrap();
                     let desc = wgc::resource::TextureViewDescriptor::default();
-                    Some(gfx_select!(texture => GLOBAL.texture_create_view(texture, &desc, PhantomData)).0)
-                }
-                wgt::SurfaceStatus::Timeout => {
-                    handle_device_error(device, &SurfaceError::Timeout);
-                    None
-                }
-                wgt::SurfaceStatus::Outdated => {
-                    handle_device_error(device, &SurfaceError::Outdated);
-                    None
-                }
-                wgt::SurfaceStatus::Lost => {
-                    handle_device_error(device, &SurfaceError::Lost);
-                    None
+                    let view = gfx_select!(texture => GLOBAL.texture_create_view(texture, &desc, PhantomData)).0;
+                    (Some(view), Some(result.status))
                 }
+                status => (None, Some(status)),
             }
         }
     }
@@ -780,7 +1166,7 @@ pub extern "C" fn wgpuSwapChainPresent(swap_chain: id::SurfaceId) {
 pub extern "C" fn wgpuTextureCreateView(
     texture: id::TextureId,
     descriptor: &native::WGPUTextureViewDescriptor,
-) -> id::TextureViewId {
+) -> Option<id::TextureViewId> {
     let desc = wgc::resource::TextureViewDescriptor {
         label: OwnedLabel::new(descriptor.label).into_cow(),
         format: conv::map_texture_format(descriptor.format),
@@ -794,7 +1180,19 @@ pub extern "C" fn wgpuTextureCreateView(
         },
     };
 
-    gfx_select!(texture => GLOBAL.texture_create_view(texture, &desc, PhantomData)).0
+    // wgpu-core validates the dimension against the texture (e.g. cube views need
+    // 6 layers), the format, and the mip/layer ranges.
+    let (id, error) =
+        gfx_select!(texture => GLOBAL.texture_create_view(texture, &desc, PhantomData));
+    if let Some(error) = error {
+        match TEXTURE_TO_DEVICE.lock().unwrap().get(&texture).copied() {
+            Some(device) => handle_device_error(device, &error),
+            None => log::error!("texture_create_view() failed: {:?}", error),
+        }
+        None
+    } else {
+        Some(id)
+    }
 }
 
 #[no_mangle]
@@ -819,10 +1217,17 @@ pub extern "C" fn wgpuDeviceCreateTexture(
         handle_device_error(device, &error);
         None
     } else {
+        TEXTURE_TO_DEVICE.lock().unwrap().insert(id, device);
         Some(id)
     }
 }
 
+lazy_static! {
+    // Swap chain textures are not tracked, errors on their views are only logged.
+    static ref TEXTURE_TO_DEVICE: Mutex<HashMap<id::TextureId, id::DeviceId>> =
+        Mutex::new(HashMap::new());
+}
+
 #[no_mangle]
 pub extern "C" fn wgpuTextureDestroy(texture_id: id::TextureId) {
     gfx_select!(texture_id => GLOBAL.texture_destroy(texture_id))
@@ -830,30 +1235,26 @@ pub extern "C" fn wgpuTextureDestroy(texture_id: id::TextureId) {
 }
 
 #[no_mangle]
-pub extern "C" fn wgpuDeviceCreateSampler(
+pub unsafe extern "C" fn wgpuDeviceCreateSampler(
     device: id::DeviceId,
     descriptor: &native::WGPUSamplerDescriptor,
 ) -> Option<id::SamplerId> {
-    let desc = wgc::resource::SamplerDescriptor {
-        label: OwnedLabel::new(descriptor.label).into_cow(),
-        address_modes: [
-            conv::map_address_mode(descriptor.addressModeU),
-            conv::map_address_mode(descriptor.addressModeV),
-            conv::map_address_mode(descriptor.addressModeW),
-        ],
-        mag_filter: conv::map_filter_mode(descriptor.magFilter),
-        min_filter: conv::map_filter_mode(descriptor.minFilter),
-        mipmap_filter: conv::map_filter_mode(descriptor.mipmapFilter),
-        lod_min_clamp: descriptor.lodMinClamp,
-        lod_max_clamp: descriptor.lodMaxClamp,
-        compare: conv::map_compare_function(descriptor.compare).ok(),
-        anisotropy_clamp: descriptor
-            .maxAnisotropy
-            .try_into()
-            .ok()
-            .and_then(|clamp| NonZeroU8::new(clamp)),
-        border_color: None,
-    };
+    let desc = follow_chain!(
+        map_sampler_descriptor(descriptor,
+        WGPUSType_SamplerDescriptorExtras => native::WGPUSamplerDescriptorExtras)
+    );
+    if desc.border_color.is_none()
+        && desc
+            .address_modes
+            .contains(&wgt::AddressMode::ClampToBorder)
+    {
+        handle_device_error_raw(
+            device,
+            native::WGPUErrorType_Validation,
+            "ClampToBorder address mode requires a border color in WGPUSamplerDescriptorExtras",
+        );
+        return None;
+    }
 
     let (id, error) =
         gfx_select!(device => GLOBAL.device_create_sampler(device, &desc, PhantomData));
@@ -877,11 +1278,13 @@ pub extern "C" fn wgpuSurface(buffer_id: id::BufferId) {
 
 #[no_mangle]
 pub extern "C" fn wgpuBufferDrop(buffer_id: id::BufferId) {
+    BUFFER_TO_DEVICE.lock().unwrap().remove(&buffer_id);
     gfx_select!(buffer_id => GLOBAL.buffer_drop(buffer_id, false))
 }
 
 #[no_mangle]
 pub extern "C" fn wgpuTextureDrop(texture_id: id::TextureId) {
+    TEXTURE_TO_DEVICE.lock().unwrap().remove(&texture_id);
     gfx_select!(texture_id => GLOBAL.texture_drop(texture_id, false))
 }
 
@@ -918,11 +1321,20 @@ pub extern "C" fn wgpuShaderModuleDrop(shader_module_id: id::ShaderModuleId) {
 
 #[no_mangle]
 pub extern "C" fn wgpuCommandEncoderDrop(command_encoder_id: id::CommandEncoderId) {
+    COMMAND_ENCODER_TO_DEVICE
+        .lock()
+        .unwrap()
+        .remove(&command_encoder_id);
+    command::forget_open_passes(command_encoder_id);
     gfx_select!(command_encoder_id => GLOBAL.command_encoder_drop(command_encoder_id))
 }
 
 #[no_mangle]
 pub extern "C" fn wgpuCommandBufferDrop(command_buffer_id: id::CommandBufferId) {
+    COMMAND_ENCODER_TO_DEVICE
+        .lock()
+        .unwrap()
+        .remove(&command_buffer_id);
     gfx_select!(command_buffer_id => GLOBAL.command_buffer_drop(command_buffer_id))
 }
 
@@ -948,5 +1360,6 @@ pub extern "C" fn wgpuComputePipelineDrop(compute_pipeline_id: id::ComputePipeli
 
 #[no_mangle]
 pub extern "C" fn wgpuDeviceDrop(device_id: id::DeviceId) {
+    clear_error_scopes(device_id);
     gfx_select!(device_id => GLOBAL.device_drop(device_id))
 }
@@ -1,6 +1,13 @@
+use conv::map_instance_descriptor;
 use log;
 use std::{
-    borrow::Cow, collections::HashMap, ffi::CString, marker::PhantomData, sync::Arc, sync::Mutex,
+    borrow::Cow,
+    collections::HashMap,
+    ffi::CString,
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, Ordering},
+    sync::Arc,
+    sync::Mutex,
 };
 use wgc::id;
 
@@ -20,8 +27,15 @@ pub mod native {
 
 type Global = wgc::hub::Global<wgc::hub::IdentityManagerFactory>;
 
+static GLOBAL_CREATED: AtomicBool = AtomicBool::new(false);
+
 lazy_static::lazy_static! {
-    static ref GLOBAL: Arc<Global> = Arc::new(Global::new("wgpu", wgc::hub::IdentityManagerFactory, wgt::Backends::PRIMARY));
+    // Set by wgpuCreateInstance, which has to run before anything touches GLOBAL.
+    static ref INSTANCE_BACKENDS: Mutex<wgt::Backends> = Mutex::new(wgt::Backends::PRIMARY);
+    static ref GLOBAL: Arc<Global> = {
+        GLOBAL_CREATED.store(true, Ordering::SeqCst);
+        Arc::new(Global::new("wgpu", wgc::hub::IdentityManagerFactory, *INSTANCE_BACKENDS.lock().unwrap()))
+    };
 }
 
 pub type Label<'a> = Option<Cow<'a, str>>;
@@ -204,6 +218,36 @@ unsafe impl raw_window_handle::HasRawWindowHandle for PseudoRwh {
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wgpuCreateInstance(
+    descriptor: Option<&native::WGPUInstanceDescriptor>,
+) -> native::WGPUInstance {
+    let backends = match descriptor {
+        Some(descriptor) => follow_chain!(
+            map_instance_descriptor(descriptor,
+            WGPUSType_InstanceExtras => native::WGPUInstanceExtras)
+        ),
+        None => wgt::Backends::PRIMARY,
+    };
+
+    {
+        let mut current = INSTANCE_BACKENDS.lock().unwrap();
+        if !GLOBAL_CREATED.load(Ordering::SeqCst) {
+            *current = backends;
+        } else if *current != backends {
+            log::warn!(
+                "Instance already created with backends {:?}, ignoring requested backends {:?}",
+                *current,
+                backends
+            );
+        }
+    }
+    lazy_static::initialize(&GLOBAL);
+
+    // There is a single global instance, the handle only needs to be non-null.
+    Arc::as_ptr(&GLOBAL) as native::WGPUInstance
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wgpuInstanceCreateSurface(
     _: native::WGPUInstance,
@@ -213,6 +257,7 @@ pub unsafe extern "C" fn wgpuInstanceCreateSurface(
         map_surface(descriptor.as_ref().unwrap(),
             WGPUSType_SurfaceDescriptorFromWindowsHWND => native::WGPUSurfaceDescriptorFromWindowsHWND,
             WGPUSType_SurfaceDescriptorFromXlib => native::WGPUSurfaceDescriptorFromXlib,
+            WGPUSType_SurfaceDescriptorFromXcbWindow => native::WGPUSurfaceDescriptorFromXcbWindow,
             WGPUSType_SurfaceDescriptorFromWaylandSurface => native::WGPUSurfaceDescriptorFromWaylandSurface,
             WGPUSType_SurfaceDescriptorFromMetalLayer => native::WGPUSurfaceDescriptorFromMetalLayer,
             WGPUSType_SurfaceDescriptorFromAndroidNativeWindow => native::WGPUSurfaceDescriptorFromAndroidNativeWindow)
@@ -227,6 +272,7 @@ unsafe fn map_surface(
     _: &native::WGPUSurfaceDescriptor,
     _win: Option<&native::WGPUSurfaceDescriptorFromWindowsHWND>,
     _x11: Option<&native::WGPUSurfaceDescriptorFromXlib>,
+    _xcb: Option<&native::WGPUSurfaceDescriptorFromXcbWindow>,
     _wl: Option<&native::WGPUSurfaceDescriptorFromWaylandSurface>,
     _metal: Option<&native::WGPUSurfaceDescriptorFromMetalLayer>,
     _android: Option<&native::WGPUSurfaceDescriptorFromAndroidNativeWindow>,
@@ -254,6 +300,14 @@ unsafe fn map_surface(
             return wgpu_create_surface(raw_window_handle::RawWindowHandle::Xlib(handle));
         }
 
+        if let Some(xcb) = _xcb {
+            let mut handle = raw_window_handle::XcbHandle::empty();
+            handle.window = xcb.window;
+            handle.connection = xcb.connection;
+
+            return wgpu_create_surface(raw_window_handle::RawWindowHandle::Xcb(handle));
+        }
+
         if let Some(wl) = _wl {
             let mut handle = raw_window_handle::WaylandHandle::empty();
             handle.display = wl.display;
@@ -276,7 +330,7 @@ unsafe fn map_surface(
         return wgpu_create_surface(raw_window_handle::RawWindowHandle::AndroidNdk(handle));
     }
 
-    panic!("Error: Unsupported Surface");
+    panic!("Error: Unsupported Surface: no descriptor in the chain matches this platform");
 }
 
 #[no_mangle]
@@ -284,12 +338,91 @@ pub unsafe extern "C" fn wgpuSurfaceGetPreferredFormat(
     surface: id::SurfaceId,
     adapter: id::AdapterId,
 ) -> native::WGPUTextureFormat {
-    let preferred_format = match wgc::gfx_select!(adapter => GLOBAL.surface_get_preferred_format(surface, adapter))
-    {
+    // wgpu-core picks an sRGB format from the supported list when there is one and
+    // falls back to the first supported format otherwise.
+    match wgc::gfx_select!(adapter => GLOBAL.surface_get_preferred_format(surface, adapter)) {
         Ok(format) => conv::to_native_texture_format(format),
-        Err(err) => panic!("Could not get preferred swap chain format: {}", err),
-    };
-    return preferred_format;
+        Err(err) => {
+            log::error!("Could not get preferred swap chain format: {}", err);
+            native::WGPUTextureFormat_Undefined
+        }
+    }
+}
+
+fn write_storage_report(report: wgc::hub::StorageReport, native: &mut native::WGPUStorageReport) {
+    native.numOccupied = report.num_occupied;
+    native.numVacant = report.num_vacant;
+    native.numError = report.num_error;
+    native.elementSize = report.element_size;
+}
+
+fn write_hub_report(report: wgc::hub::HubReport, native: &mut native::WGPUHubReport) {
+    write_storage_report(report.adapters, &mut native.adapters);
+    write_storage_report(report.devices, &mut native.devices);
+    write_storage_report(report.pipeline_layouts, &mut native.pipelineLayouts);
+    write_storage_report(report.shader_modules, &mut native.shaderModules);
+    write_storage_report(report.bind_group_layouts, &mut native.bindGroupLayouts);
+    write_storage_report(report.bind_groups, &mut native.bindGroups);
+    write_storage_report(report.command_buffers, &mut native.commandBuffers);
+    write_storage_report(report.render_bundles, &mut native.renderBundles);
+    write_storage_report(report.render_pipelines, &mut native.renderPipelines);
+    write_storage_report(report.compute_pipelines, &mut native.computePipelines);
+    write_storage_report(report.query_sets, &mut native.querySets);
+    write_storage_report(report.buffers, &mut native.buffers);
+    write_storage_report(report.textures, &mut native.textures);
+    write_storage_report(report.texture_views, &mut native.textureViews);
+    write_storage_report(report.samplers, &mut native.samplers);
+}
+
+fn write_backend_type(backend: native::WGPUBackendType, native: &mut native::WGPUGlobalReport) {
+    if native.backendType == native::WGPUBackendType_Null {
+        native.backendType = backend;
+    }
+}
+
+/// Fills `report` with the number of live, vacant and errored ids per resource type,
+/// for every backend compiled in. Hubs of backends that are not compiled in or not in
+/// use are zeroed. `backendType` is the first backend in Vulkan, Metal, DX12, GL order
+/// whose hub was filled in, or Null if there is none.
+#[no_mangle]
+pub extern "C" fn wgpuGenerateReport(report: &mut native::WGPUGlobalReport) {
+    let global_report = GLOBAL.generate_report();
+    // Plain counters, all zeroes is a valid empty report.
+    *report = unsafe { std::mem::zeroed() };
+    report.backendType = native::WGPUBackendType_Null;
+    write_storage_report(global_report.surfaces, &mut report.surfaces);
+
+    // Gated like the hubs in wgpu-core's build script. wgpu-core doesn't build DX11
+    // at this version, so that hub stays zeroed.
+    #[cfg(all(
+        not(target_arch = "wasm32"),
+        any(windows, all(unix, not(any(target_os = "ios", target_os = "macos"))))
+    ))]
+    if let Some(hub) = global_report.vulkan {
+        write_backend_type(native::WGPUBackendType_Vulkan, report);
+        write_hub_report(hub, &mut report.vulkan);
+    }
+    #[cfg(all(
+        not(target_arch = "wasm32"),
+        any(target_os = "ios", target_os = "macos")
+    ))]
+    if let Some(hub) = global_report.metal {
+        write_backend_type(native::WGPUBackendType_Metal, report);
+        write_hub_report(hub, &mut report.metal);
+    }
+    #[cfg(all(not(target_arch = "wasm32"), windows))]
+    if let Some(hub) = global_report.dx12 {
+        write_backend_type(native::WGPUBackendType_D3D12, report);
+        write_hub_report(hub, &mut report.dx12);
+    }
+    #[cfg(any(
+        target_arch = "wasm32",
+        all(unix, not(any(target_os = "ios", target_os = "macos")))
+    ))]
+    if let Some(hub) = global_report.gl {
+        write_backend_type(native::WGPUBackendType_OpenGL, report);
+        write_hub_report(hub, &mut report.gl);
+    }
 }
 
 struct DeviceCallback<T> {
@@ -340,19 +473,97 @@ pub unsafe extern "C" fn wgpuDeviceSetDeviceLostCallback(
         .insert(device, DeviceLostCallback { callback, userdata });
 }
 
+struct ErrorScope {
+    filter: native::WGPUErrorFilter,
+    error: Option<(native::WGPUErrorType, String)>,
+}
+
+impl ErrorScope {
+    fn captures(&self, typ: native::WGPUErrorType) -> bool {
+        match self.filter {
+            native::WGPUErrorFilter_Validation => typ == native::WGPUErrorType_Validation,
+            native::WGPUErrorFilter_OutOfMemory => typ == native::WGPUErrorType_OutOfMemory,
+            _ => false,
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref ERROR_SCOPES: Mutex<HashMap<id::DeviceId, Vec<ErrorScope>>> =
+        Mutex::new(HashMap::new());
+}
+
+#[no_mangle]
+pub extern "C" fn wgpuDevicePushErrorScope(device: id::DeviceId, filter: native::WGPUErrorFilter) {
+    ERROR_SCOPES
+        .lock()
+        .unwrap()
+        .entry(device)
+        .or_default()
+        .push(ErrorScope {
+            filter,
+            error: None,
+        });
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wgpuDevicePopErrorScope(
+    device: id::DeviceId,
+    callback: native::WGPUErrorCallback,
+    userdata: *mut std::os::raw::c_void,
+) -> bool {
+    let scope = ERROR_SCOPES
+        .lock()
+        .unwrap()
+        .get_mut(&device)
+        .and_then(|scopes| scopes.pop());
+    // Popping an empty stack is an error and the callback is not invoked.
+    let scope = match scope {
+        Some(scope) => scope,
+        None => return false,
+    };
+    let (typ, msg) = scope
+        .error
+        .unwrap_or((native::WGPUErrorType_NoError, String::new()));
+    let msg_c = CString::new(msg).unwrap();
+    callback.unwrap()(typ, msg_c.as_ptr(), userdata);
+    true
+}
+
+// Scopes still pushed when the device is dropped can never be popped.
+pub(crate) fn clear_error_scopes(device: id::DeviceId) {
+    ERROR_SCOPES.lock().unwrap().remove(&device);
+}
+
 pub fn handle_device_error_raw(device: id::DeviceId, typ: native::WGPUErrorType, msg: &str) {
     log::debug!("Device error ({}): {}", typ, msg);
+
+    // The innermost scope whose filter matches captures the error; only the first
+    // error is kept, later ones in the same scope are dropped.
+    if let Some(scope) = ERROR_SCOPES
+        .lock()
+        .unwrap()
+        .get_mut(&device)
+        .and_then(|scopes| scopes.iter_mut().rev().find(|scope| scope.captures(typ)))
+    {
+        if scope.error.is_none() {
+            scope.error = Some((typ, msg.to_string()));
+        }
+        return;
+    }
+
     let msg_c = CString::new(msg).unwrap();
     unsafe {
         match typ {
             native::WGPUErrorType_DeviceLost => {
-                let cbs = CALLBACKS.lock().unwrap();
-                let cb = cbs.device_lost.get(&device);
+                // A device is only lost once, so the callback is fired a single time
+                // even though every later call on the device keeps failing.
+                let cb = CALLBACKS.lock().unwrap().device_lost.remove(&device);
                 if let Some(cb) = cb {
-                    (*cb).callback.unwrap()(
-                        native::WGPUDeviceLostReason_Destroyed,
+                    cb.callback.unwrap()(
+                        native::WGPUDeviceLostReason_Undefined,
                         msg_c.as_ptr(),
-                        (*cb).userdata,
+                        cb.userdata,
                     );
                 }
             }
@@ -367,16 +578,71 @@ pub fn handle_device_error_raw(device: id::DeviceId, typ: native::WGPUErrorType,
     }
 }
 
-pub fn handle_device_error<E: std::any::Any + std::error::Error>(device: id::DeviceId, error: &E) {
-    let error_any = error as &dyn std::any::Any;
-
-    let typ = match error_any.downcast_ref::<wgc::device::DeviceError>() {
-        Some(device_error) => match device_error {
-            wgc::device::DeviceError::Lost => native::WGPUErrorType_DeviceLost,
-            _ => native::WGPUErrorType_Unknown,
-        },
-        None => native::WGPUErrorType_Unknown,
-    };
+fn map_device_error(error: &wgc::device::DeviceError) -> native::WGPUErrorType {
+    match error {
+        wgc::device::DeviceError::Lost => native::WGPUErrorType_DeviceLost,
+        wgc::device::DeviceError::OutOfMemory => native::WGPUErrorType_OutOfMemory,
+        _ => native::WGPUErrorType_Validation,
+    }
+}
 
+// wgpu-core wraps DeviceError in `#[error(transparent)]` variants, which forward
+// source() to the inner error's source, so the DeviceError never shows up in the
+// source chain. Each wrapping type has to be matched explicitly instead.
+macro_rules! classify_error {
+    ($error:expr, $($ty:ty $(=> $variant:path)?),+ $(,)?) => {{
+        let error: &dyn std::any::Any = $error;
+        $(
+            if let Some(_error) = error.downcast_ref::<$ty>() {
+                $(
+                    if let $variant(device_error) = _error {
+                        return map_device_error(device_error);
+                    }
+                )?
+                return native::WGPUErrorType_Validation;
+            }
+        )+
+    }};
+}
+
+fn error_type(error: &dyn std::any::Any) -> native::WGPUErrorType {
+    use wgc::{binding_model, command, device, pipeline, resource};
+
+    if let Some(device_error) = error.downcast_ref::<device::DeviceError>() {
+        return map_device_error(device_error);
+    }
+    classify_error!(
+        error,
+        resource::CreateBufferError => resource::CreateBufferError::Device,
+        resource::CreateTextureError => resource::CreateTextureError::Device,
+        resource::CreateSamplerError => resource::CreateSamplerError::Device,
+        resource::CreateQuerySetError => resource::CreateQuerySetError::Device,
+        resource::BufferAccessError => resource::BufferAccessError::Device,
+        binding_model::CreateBindGroupLayoutError => binding_model::CreateBindGroupLayoutError::Device,
+        binding_model::CreateBindGroupError => binding_model::CreateBindGroupError::Device,
+        binding_model::CreatePipelineLayoutError => binding_model::CreatePipelineLayoutError::Device,
+        pipeline::CreateShaderModuleError => pipeline::CreateShaderModuleError::Device,
+        pipeline::CreateRenderPipelineError => pipeline::CreateRenderPipelineError::Device,
+        pipeline::CreateComputePipelineError => pipeline::CreateComputePipelineError::Device,
+        device::queue::QueueSubmitError => device::queue::QueueSubmitError::Queue,
+        device::queue::QueueWriteError => device::queue::QueueWriteError::Queue,
+        wgc::present::ConfigureSurfaceError => wgc::present::ConfigureSurfaceError::Device,
+        wgc::present::SurfaceError => wgc::present::SurfaceError::Device,
+        resource::CreateTextureViewError,
+        command::CommandEncoderError,
+        command::CopyError,
+        command::QueryError,
+        command::ClearError,
+        command::ComputePassError,
+        command::RenderPassError,
+        command::CreateRenderBundleError,
+        command::RenderBundleError,
+        naga::front::spv::Error,
+    );
+    native::WGPUErrorType_Unknown
+}
+
+pub fn handle_device_error<E: std::any::Any + std::error::Error>(device: id::DeviceId, error: &E) {
+    let typ = error_type(error);
     handle_device_error_raw(device, typ, &format!("{:?}", error));
 }